@@ -0,0 +1,213 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Whether requests are currently allowed through to funtranslations.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum CircuitState {
+    /// Requests are let through as normal.
+    Closed,
+    /// Too many requests failed, so requests are rejected until the cool down has passed.
+    Open,
+    /// The cool down has passed, and a single trial request decides whether to close or reopen.
+    HalfOpen,
+}
+
+/// Stops calling an upstream that keeps failing, and periodically checks whether it has recovered.
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cool_down: Duration,
+    state: Mutex<BreakerState>,
+}
+
+enum BreakerState {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    HalfOpen { trial_started: Option<Instant> },
+}
+
+impl CircuitBreaker {
+    /// Opens after `failure_threshold` consecutive failures, staying open for `cool_down`.
+    pub fn new(failure_threshold: u32, cool_down: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            cool_down,
+            state: Mutex::new(BreakerState::Closed { failures: 0 }),
+        }
+    }
+
+    pub fn state(&self) -> CircuitState {
+        let mut state = self.state.lock().unwrap();
+        self.advance(&mut state);
+        match *state {
+            BreakerState::Closed { .. } => CircuitState::Closed,
+            BreakerState::Open { .. } => CircuitState::Open,
+            BreakerState::HalfOpen { .. } => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Returns false if the request shouldn't be made. Every allowed request must be followed by
+    /// a call to `record_success`, `record_failure` or `release`.
+    pub fn try_acquire(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        self.advance(&mut state);
+        match &mut *state {
+            BreakerState::Closed { .. } => true,
+            BreakerState::Open { .. } => false,
+            BreakerState::HalfOpen { trial_started } => match trial_started {
+                // A trial that's taken longer than the cool down was most likely cancelled
+                Some(started) if started.elapsed() < self.cool_down => false,
+                _ => {
+                    *trial_started = Some(Instant::now());
+                    true
+                }
+            },
+        }
+    }
+
+    pub fn record_success(&self) {
+        let mut state = self.state.lock().unwrap();
+        if let BreakerState::HalfOpen { .. } = *state {
+            info!("Translation circuit breaker closed");
+        }
+        *state = BreakerState::Closed { failures: 0 };
+    }
+
+    pub fn record_failure(&self) {
+        let mut state = self.state.lock().unwrap();
+        match &mut *state {
+            BreakerState::Closed { failures } => {
+                *failures += 1;
+                if *failures >= self.failure_threshold {
+                    warn!(failures = *failures, "Translation circuit breaker opened");
+                    *state = BreakerState::Open {
+                        until: Instant::now() + self.cool_down,
+                    };
+                }
+            }
+            BreakerState::HalfOpen { .. } => {
+                warn!("Translation circuit breaker reopened after a failed trial request");
+                *state = BreakerState::Open {
+                    until: Instant::now() + self.cool_down,
+                };
+            }
+            BreakerState::Open { .. } => {}
+        }
+    }
+
+    /// Gives back an allowed request that was never made, without affecting the state.
+    pub fn release(&self) {
+        let mut state = self.state.lock().unwrap();
+        if let BreakerState::HalfOpen { trial_started } = &mut *state {
+            *trial_started = None;
+        }
+    }
+
+    fn advance(&self, state: &mut BreakerState) {
+        if let BreakerState::Open { until } = state {
+            if Instant::now() >= *until {
+                *state = BreakerState::HalfOpen {
+                    trial_started: None,
+                };
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_opens_after_the_failure_threshold() {
+        // arrange
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(60));
+
+        // act
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        breaker.record_failure();
+
+        // assert
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(!breaker.try_acquire());
+    }
+
+    #[test]
+    fn it_resets_the_failure_count_on_success() {
+        // arrange
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(60));
+
+        // act
+        breaker.record_failure();
+        breaker.record_success();
+        breaker.record_failure();
+
+        // assert
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(breaker.try_acquire());
+    }
+
+    #[test]
+    fn it_allows_a_single_trial_request_after_the_cool_down() {
+        // arrange
+        let breaker = CircuitBreaker::new(1, Duration::from_millis(20));
+        breaker.record_failure();
+
+        // act
+        std::thread::sleep(Duration::from_millis(30));
+
+        // assert
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        assert!(breaker.try_acquire());
+        assert!(!breaker.try_acquire());
+    }
+
+    #[test]
+    fn it_closes_after_a_successful_trial() {
+        // arrange
+        let breaker = CircuitBreaker::new(1, Duration::from_millis(20));
+        breaker.record_failure();
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(breaker.try_acquire());
+
+        // act
+        breaker.record_success();
+
+        // assert
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn it_reopens_after_a_failed_trial() {
+        // arrange
+        let breaker = CircuitBreaker::new(1, Duration::from_millis(20));
+        breaker.record_failure();
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(breaker.try_acquire());
+
+        // act
+        breaker.record_failure();
+
+        // assert
+        assert_eq!(breaker.state(), CircuitState::Open);
+    }
+
+    #[test]
+    fn it_allows_another_trial_after_a_release() {
+        // arrange
+        let breaker = CircuitBreaker::new(1, Duration::from_millis(20));
+        breaker.record_failure();
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(breaker.try_acquire());
+
+        // act
+        breaker.release();
+
+        // assert
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        assert!(breaker.try_acquire());
+    }
+}
@@ -0,0 +1,66 @@
+pub use http_support::ErrorContext;
+use std::mem::discriminant;
+use thiserror::Error;
+
+/// Why translating text failed.
+///
+/// Errors compare equal when they are the same variant, whatever their context.
+#[derive(Error, Debug, Clone)]
+pub enum TranslationError {
+    #[error("Failed to deserialize translation data{0}")]
+    Deserialization(ErrorContext),
+    #[error("Failed to get translation{0}")]
+    Api(ErrorContext),
+    #[error("Failed to get translation, too many requests")]
+    TooManyRequests,
+    #[error("Failed to get translation, circuit breaker is open")]
+    CircuitOpen,
+    #[error("Failed to get translation, timed out")]
+    Timeout,
+    #[error("Failed to get translation, not supported offline")]
+    Unsupported,
+}
+
+impl PartialEq for TranslationError {
+    fn eq(&self, other: &Self) -> bool {
+        discriminant(self) == discriminant(other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_describes_the_context() {
+        // arrange
+        let error = TranslationError::Deserialization(
+            ErrorContext::default()
+                .with_status(200)
+                .with_source("missing field `contents`")
+                .with_body(" {\"name\": \"success\"}\n"),
+        );
+
+        // act
+        let message = error.to_string();
+
+        // assert
+        assert_eq!(
+            message,
+            "Failed to deserialize translation data: status 200, missing field `contents`, body \"{\\\"name\\\": \\\"success\\\"}\""
+        );
+    }
+
+    #[test]
+    fn it_compares_errors_by_kind() {
+        // arrange
+        let with_context = TranslationError::Api(ErrorContext::default().with_status(500));
+
+        // act
+        let without_context = TranslationError::Api(ErrorContext::default());
+
+        // assert
+        assert_eq!(with_context, without_context);
+        assert_ne!(with_context, TranslationError::Timeout);
+    }
+}
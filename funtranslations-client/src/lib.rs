@@ -0,0 +1,38 @@
+//! A client for the [funtranslations](https://funtranslations.com) api.
+//!
+//! Requests can be rate limited, guarded by a circuit breaker and cached on disk. Shakespeare and
+//! Yoda translations can also be done offline with [`LocalTranslator`], either instead of or as a
+//! fallback for the api.
+//!
+//! ```no_run
+//! use funtranslations_client::{LocalTranslation, TranslationClient, TranslationType};
+//!
+//! # async fn example() -> Result<(), funtranslations_client::TranslationError> {
+//! let client = TranslationClient::new("https://api.funtranslations.com".into(), None)
+//!     .with_local_translation(LocalTranslation::Fallback);
+//! let translated = client
+//!     .get_translation("You are brave", TranslationType::SHAKESPEARE)
+//!     .await?;
+//! println!("{}", translated);
+//! # Ok(())
+//! # }
+//! ```
+
+mod circuit_breaker;
+mod error;
+mod local_translator;
+mod observer;
+mod rate_limit;
+mod translation_cache;
+mod translation_client;
+
+pub use circuit_breaker::CircuitState;
+pub use error::{ErrorContext, TranslationError};
+pub use http_support::{BuildClientError, Timeouts};
+pub use local_translator::{LocalTranslation, LocalTranslator};
+pub use observer::TranslationObserver;
+pub use translation_cache::TranslationCache;
+pub use translation_client::{
+    Translation, TranslationClient, TranslationResponse, TranslationSource, TranslationSuccess,
+    TranslationTextContents, TranslationType,
+};
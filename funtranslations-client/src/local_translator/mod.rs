@@ -0,0 +1,90 @@
+mod shakespeare;
+mod text;
+mod yoda;
+
+use crate::error::TranslationError;
+use crate::translation_client::TranslationType;
+use serde::Deserialize;
+
+/// How the offline translator is used alongside the funtranslations api.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum LocalTranslation {
+    /// Only use funtranslations
+    Disabled,
+    /// Translate offline when funtranslations fails
+    Fallback,
+    /// Translate offline, and only use funtranslations for styles that can't be translated offline
+    Primary,
+}
+
+/// Deterministic, rule based approximations of some funtranslations styles, so descriptions can
+/// still be translated when the api is rate limited or down.
+#[derive(Clone, Debug, Default)]
+pub struct LocalTranslator;
+
+impl LocalTranslator {
+    pub fn supports(&self, translation_type: TranslationType) -> bool {
+        translation_type == TranslationType::SHAKESPEARE
+            || translation_type == TranslationType::YODA
+    }
+
+    pub async fn get_translation(
+        &self,
+        text: &str,
+        translation_type: TranslationType,
+    ) -> Result<String, TranslationError> {
+        match translation_type {
+            TranslationType::SHAKESPEARE => Ok(shakespeare::translate(text)),
+            TranslationType::YODA => Ok(yoda::translate(text)),
+            _ => Err(TranslationError::Unsupported),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn it_translates_shakespeare() {
+        // arrange
+        let translator = LocalTranslator;
+
+        // act
+        let result = translator
+            .get_translation("Is that you?", TranslationType::SHAKESPEARE)
+            .await;
+
+        // assert
+        assert_eq!(result, Ok("Is yond thee?".to_string()));
+    }
+
+    #[tokio::test]
+    async fn it_rejects_unsupported_translations() {
+        // arrange
+        let translator = LocalTranslator;
+        let klingon = TranslationType::from_name("klingon").unwrap();
+
+        // act
+        let result = translator.get_translation("Hello", klingon).await;
+
+        // assert
+        assert!(!translator.supports(klingon));
+        assert_eq!(result, Err(TranslationError::Unsupported));
+    }
+
+    #[tokio::test]
+    async fn it_translates_yoda() {
+        // arrange
+        let translator = LocalTranslator;
+
+        // act
+        let result = translator
+            .get_translation("Hides in caves.", TranslationType::YODA)
+            .await;
+
+        // assert
+        assert_eq!(result, Ok("In caves, hides.".to_string()));
+    }
+}
@@ -0,0 +1,269 @@
+//! A rule based approximation of funtranslations' Shakespeare translator.
+
+use super::text::{capitalize_like, join, tokenize, Token};
+
+/// Two word phrases, which take priority over translating the words on their own.
+const PHRASES: [(&str, &str, &str); 16] = [
+    ("you", "are", "thou art"),
+    ("you", "were", "thou wert"),
+    ("you", "have", "thou hast"),
+    ("you", "do", "thou dost"),
+    ("you", "will", "thou wilt"),
+    ("you", "shall", "thou shalt"),
+    ("you", "can", "thou canst"),
+    ("are", "you", "art thou"),
+    ("were", "you", "wert thou"),
+    ("have", "you", "hast thou"),
+    ("do", "you", "dost thou"),
+    ("will", "you", "wilt thou"),
+    ("can", "you", "canst thou"),
+    ("it", "is", "'tis"),
+    ("it", "was", "'twas"),
+    ("it", "were", "'twere"),
+];
+
+const WORDS: [(&str, &str); 27] = [
+    ("you're", "thou art"),
+    ("yours", "thine"),
+    ("yourself", "thyself"),
+    ("that", "yond"),
+    ("enough", "enow"),
+    ("yes", "aye"),
+    ("hello", "hail"),
+    ("hi", "hail"),
+    ("before", "ere"),
+    ("often", "oft"),
+    ("nothing", "naught"),
+    ("over", "o'er"),
+    ("never", "ne'er"),
+    ("ever", "e'er"),
+    ("even", "e'en"),
+    ("perhaps", "perchance"),
+    ("maybe", "perchance"),
+    ("between", "betwixt"),
+    ("among", "amongst"),
+    ("why", "wherefore"),
+    ("soon", "anon"),
+    ("does", "doth"),
+    ("has", "hath"),
+    ("kill", "slay"),
+    ("killed", "slain"),
+    ("friend", "sirrah"),
+    ("isn't", "is not"),
+];
+
+fn starts_with_vowel(word: &str) -> bool {
+    word.chars()
+        .next()
+        .is_some_and(|c| "aeiouAEIOU".contains(c))
+}
+
+/// Translates a single word, given the word that follows it and whether it starts a sentence.
+fn translate_word(word: &str, next: Option<&str>, starts_sentence: bool) -> String {
+    let lower = word.to_lowercase();
+    let translated = match lower.as_str() {
+        // "You" is the subject at the start of a sentence, and usually the object elsewhere
+        "you" if starts_sentence => "thou",
+        "you" => "thee",
+        "your" if next.is_some_and(starts_with_vowel) => "thine",
+        "your" => "thy",
+        "my" if next.is_some_and(starts_with_vowel) => "mine",
+        _ => match WORDS.iter().find(|(english, _)| *english == lower) {
+            Some((_, shakespearean)) => shakespearean,
+            None => return word.to_string(),
+        },
+    };
+    capitalize_like(word, translated)
+}
+
+pub fn translate(text: &str) -> String {
+    let tokens = tokenize(text);
+    let mut output = vec![];
+    let mut starts_sentence = true;
+
+    let mut index = 0;
+    while index < tokens.len() {
+        match &tokens[index] {
+            Token::Word(word) => {
+                let next_word = match (tokens.get(index + 1), tokens.get(index + 2)) {
+                    (Some(Token::Other(space)), Some(Token::Word(next))) if space == " " => {
+                        Some(next.as_str())
+                    }
+                    _ => None,
+                };
+
+                let phrase = next_word.and_then(|next| {
+                    PHRASES.iter().find(|(first, second, _)| {
+                        word.eq_ignore_ascii_case(first) && next.eq_ignore_ascii_case(second)
+                    })
+                });
+
+                match phrase {
+                    Some((_, _, shakespearean)) => {
+                        output.push(Token::Word(capitalize_like(word, shakespearean)));
+                        index += 3;
+                    }
+                    None => {
+                        let word_after = match (tokens.get(index + 1), tokens.get(index + 2)) {
+                            (Some(Token::Other(_)), Some(Token::Word(next))) => Some(next.as_str()),
+                            _ => None,
+                        };
+                        output.push(Token::Word(translate_word(
+                            word,
+                            word_after,
+                            starts_sentence,
+                        )));
+                        index += 1;
+                    }
+                }
+                starts_sentence = false;
+            }
+            Token::Other(other) => {
+                if other.contains(['.', '!', '?']) {
+                    starts_sentence = true;
+                }
+                output.push(Token::Other(other.clone()));
+                index += 1;
+            }
+        }
+    }
+    join(&output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_translates_the_charizard_description_like_funtranslations() {
+        // arrange
+        let text = "Spits fire that is hot enough to melt boulders. Known to cause forest fires unintentionally.";
+
+        // act
+        let translated = translate(text);
+
+        // assert
+        assert_eq!(translated, "Spits fire yond is hot enow to melt boulders. Known to cause forest fires unintentionally.");
+    }
+
+    #[test]
+    fn it_leaves_text_without_known_words_alone() {
+        // arrange
+        let text = "Forms colonies in perpetually dark places. Uses ultrasonic waves to identify and approach targets.";
+
+        // act
+        let translated = translate(text);
+
+        // assert
+        assert_eq!(translated, "Forms colonies in perpetually dark places. Uses ultrasonic waves to identify and approach targets.");
+    }
+
+    #[test]
+    fn it_translates_hello_world() {
+        // arrange
+        let text = "Hello world";
+
+        // act
+        let translated = translate(text);
+
+        // assert
+        assert_eq!(translated, "Hail world");
+    }
+
+    #[test]
+    fn it_conjugates_verbs_after_you() {
+        // arrange
+        let cases = vec![
+            ("You are brave", "Thou art brave"),
+            ("Where have you been?", "Where hast thou been?"),
+            ("you will see", "thou wilt see"),
+        ];
+
+        for (text, expected) in cases {
+            // act
+            let translated = translate(text);
+
+            // assert
+            assert_eq!(translated, expected);
+        }
+    }
+
+    #[test]
+    fn it_uses_thou_for_subjects_and_thee_for_objects() {
+        // arrange
+        let text = "You know it burns you.";
+
+        // act
+        let translated = translate(text);
+
+        // assert
+        assert_eq!(translated, "Thou know it burns thee.");
+    }
+
+    #[test]
+    fn it_uses_thine_and_mine_before_vowels() {
+        // arrange
+        let cases = vec![
+            ("Your eyes and your hands", "Thine eyes and thy hands"),
+            ("my eyes, my hands", "mine eyes, my hands"),
+        ];
+
+        for (text, expected) in cases {
+            // act
+            let translated = translate(text);
+
+            // assert
+            assert_eq!(translated, expected);
+        }
+    }
+
+    #[test]
+    fn it_contracts_it_is() {
+        // arrange
+        let text = "It is cold. it was colder";
+
+        // act
+        let translated = translate(text);
+
+        // assert
+        assert_eq!(translated, "'Tis cold. 'twas colder");
+    }
+
+    #[test]
+    fn it_keeps_punctuation_and_whitespace() {
+        // arrange
+        let text = "Enough!  Never,\never again...";
+
+        // act
+        let translated = translate(text);
+
+        // assert
+        assert_eq!(translated, "Enow!  Ne'er,\ne'er again...");
+    }
+
+    #[test]
+    fn it_preserves_uppercase_words() {
+        // arrange
+        let text = "THAT is ENOUGH";
+
+        // act
+        let translated = translate(text);
+
+        // assert
+        assert_eq!(translated, "YOND is ENOW");
+    }
+
+    #[test]
+    fn it_is_deterministic() {
+        // arrange
+        let text = "Perhaps you have never seen it before, friend.";
+
+        // act
+        let first = translate(text);
+        let second = translate(text);
+
+        // assert
+        assert_eq!(first, second);
+        assert_eq!(first, "Perchance thou hast ne'er seen it ere, sirrah.");
+    }
+}
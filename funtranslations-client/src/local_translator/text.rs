@@ -0,0 +1,71 @@
+/// A run of word characters, or of everything in between them.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Token {
+    Word(String),
+    Other(String),
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '\'' || c == '-'
+}
+
+pub fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut current = String::new();
+    let mut in_word = false;
+    for c in text.chars() {
+        if is_word_char(c) != in_word && !current.is_empty() {
+            let token = std::mem::take(&mut current);
+            tokens.push(if in_word {
+                Token::Word(token)
+            } else {
+                Token::Other(token)
+            });
+        }
+        in_word = is_word_char(c);
+        current.push(c);
+    }
+    if !current.is_empty() {
+        tokens.push(if in_word {
+            Token::Word(current)
+        } else {
+            Token::Other(current)
+        });
+    }
+    tokens
+}
+
+pub fn join(tokens: &[Token]) -> String {
+    tokens
+        .iter()
+        .map(|token| match token {
+            Token::Word(text) | Token::Other(text) => text.as_str(),
+        })
+        .collect()
+}
+
+/// Copies the capitalisation of `original` onto `replacement`.
+pub fn capitalize_like(original: &str, replacement: &str) -> String {
+    let mut letters = original.chars().filter(|c| c.is_alphabetic());
+    match letters.next() {
+        Some(first) if first.is_uppercase() => {
+            if letters.clone().next().is_some() && letters.all(char::is_uppercase) {
+                return replacement.to_uppercase();
+            }
+            match replacement.find(char::is_alphabetic) {
+                Some(index) => {
+                    let (before, rest) = replacement.split_at(index);
+                    let mut chars = rest.chars();
+                    let first: String = chars
+                        .next()
+                        .into_iter()
+                        .flat_map(char::to_uppercase)
+                        .collect();
+                    format!("{}{}{}", before, first, chars.as_str())
+                }
+                None => replacement.to_string(),
+            }
+        }
+        _ => replacement.to_string(),
+    }
+}
@@ -0,0 +1,208 @@
+//! A rule based approximation of funtranslations' Yoda translator, which moves the end of each
+//! sentence to the front.
+
+const CONJUNCTIONS: [&str; 2] = ["and", "but"];
+
+const BE_VERBS: [&str; 5] = ["am", "is", "are", "was", "were"];
+
+const PREPOSITIONS: [&str; 10] = [
+    "in", "on", "at", "with", "from", "into", "by", "for", "through", "under",
+];
+
+fn is_one_of(word: &str, words: &[&str]) -> bool {
+    let word = word.trim_matches(|c: char| !c.is_alphanumeric());
+    words.iter().any(|w| w.eq_ignore_ascii_case(word))
+}
+
+fn capitalize(clause: &str) -> String {
+    let mut chars = clause.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Lowercases the first word, unless it's "I" or an acronym.
+fn uncapitalize(clause: &str) -> String {
+    let first_word = clause.split_whitespace().next().unwrap_or_default();
+    if first_word == "I"
+        || first_word.chars().filter(|c| c.is_alphabetic()).count() > 1
+            && first_word.chars().all(|c| !c.is_lowercase())
+    {
+        return clause.to_string();
+    }
+    let mut chars = clause.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Finds the index of the first word moved to the front. A sentence is split before its last
+/// conjunction, then after its first "to be" verb, then before its last preposition.
+fn split_point(words: &[&str]) -> Option<usize> {
+    let inner = 1..words.len().saturating_sub(1);
+    inner
+        .clone()
+        .rev()
+        .find(|&i| is_one_of(words[i], &CONJUNCTIONS))
+        .or_else(|| {
+            inner
+                .clone()
+                .find(|&i| is_one_of(words[i], &BE_VERBS))
+                .map(|i| i + 1)
+        })
+        .or_else(|| inner.rev().find(|&i| is_one_of(words[i], &PREPOSITIONS)))
+}
+
+fn translate_sentence(sentence: &str) -> String {
+    let words: Vec<&str> = sentence.split_whitespace().collect();
+    match split_point(&words) {
+        Some(index) => {
+            let head = words[..index].join(" ");
+            let tail = words[index..].join(" ");
+            format!(
+                "{}, {}",
+                capitalize(tail.trim_end_matches(',')),
+                uncapitalize(head.trim_end_matches(','))
+            )
+        }
+        None => sentence.to_string(),
+    }
+}
+
+pub fn translate(text: &str) -> String {
+    let mut output = String::new();
+    let mut sentence = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if matches!(c, '.' | '!' | '?') {
+            output.push_str(&translate_sentence(&sentence));
+            sentence.clear();
+            output.push(c);
+            // Keep the rest of the punctuation and whitespace between sentences as it is
+            while let Some(&next) = chars.peek() {
+                if !(matches!(next, '.' | '!' | '?') || next.is_whitespace()) {
+                    break;
+                }
+                output.push(next);
+                chars.next();
+            }
+        } else {
+            sentence.push(c);
+        }
+    }
+    output.push_str(&translate_sentence(&sentence));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_moves_the_clause_after_the_last_conjunction_to_the_front() {
+        // arrange
+        let text = "Uses ultrasonic waves to identify and approach targets.";
+
+        // act
+        let translated = translate(text);
+
+        // assert
+        assert_eq!(
+            translated,
+            "And approach targets, uses ultrasonic waves to identify."
+        );
+    }
+
+    /// Funtranslations leaves the first sentence alone, unlike this. Splitting on the last
+    /// preposition is intentional, so more sentences sound like Yoda offline.
+    #[test]
+    fn it_translates_the_zubat_description() {
+        // arrange
+        let text = "Forms colonies in perpetually dark places. Uses ultrasonic waves to identify and approach targets.";
+
+        // act
+        let translated = translate(text);
+
+        // assert
+        assert_eq!(translated, "In perpetually dark places, forms colonies. And approach targets, uses ultrasonic waves to identify.");
+    }
+
+    #[test]
+    fn it_moves_the_complement_of_to_be_to_the_front() {
+        // arrange
+        let cases = vec![
+            (
+                "Spits fire that is hot enough to melt boulders. Known to cause forest fires unintentionally.",
+                "Hot enough to melt boulders, spits fire that is. Known to cause forest fires unintentionally.",
+            ),
+            ("You are strong!", "Strong, you are!"),
+        ];
+
+        for (text, expected) in cases {
+            // act
+            let translated = translate(text);
+
+            // assert
+            assert_eq!(translated, expected);
+        }
+    }
+
+    #[test]
+    fn it_keeps_i_and_acronyms_capitalised() {
+        // arrange
+        let cases = vec![
+            ("I am your father", "Your father, I am"),
+            ("HP is low", "Low, HP is"),
+        ];
+
+        for (text, expected) in cases {
+            // act
+            let translated = translate(text);
+
+            // assert
+            assert_eq!(translated, expected);
+        }
+    }
+
+    #[test]
+    fn it_leaves_sentences_it_cannot_split_alone() {
+        // arrange
+        let cases = vec![("Hello world", "Hello world"), ("", "")];
+
+        for (text, expected) in cases {
+            // act
+            let translated = translate(text);
+
+            // assert
+            assert_eq!(translated, expected);
+        }
+    }
+
+    #[test]
+    fn it_keeps_the_punctuation_between_sentences() {
+        // arrange
+        let text = "It was late...  The moon is full?! Sleep";
+
+        // act
+        let translated = translate(text);
+
+        // assert
+        assert_eq!(translated, "Late, it was...  Full, the moon is?! Sleep");
+    }
+
+    #[test]
+    fn it_is_deterministic() {
+        // arrange
+        let text = "Lives in caves and hunts at night.";
+
+        // act
+        let first = translate(text);
+        let second = translate(text);
+
+        // assert
+        assert_eq!(first, second);
+        assert_eq!(first, "And hunts at night, lives in caves.");
+    }
+}
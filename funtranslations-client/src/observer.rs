@@ -0,0 +1,16 @@
+use crate::error::TranslationError;
+use std::time::Duration;
+
+/// Notified about requests to funtranslations and cache lookups, for example to record metrics.
+pub trait TranslationObserver: Send + Sync {
+    /// Called after every request sent to funtranslations, with how long it took.
+    /// Requests rejected by the rate limit or circuit breaker are never sent.
+    fn on_request(&self, _duration: Duration, _error: Option<&TranslationError>) {}
+
+    /// Called for every lookup in the translation cache.
+    fn on_cache_lookup(&self, _hit: bool) {}
+}
+
+pub(crate) struct NoopObserver;
+
+impl TranslationObserver for NoopObserver {}
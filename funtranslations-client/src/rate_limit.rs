@@ -0,0 +1,157 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A token bucket rate limiter, which can also be blocked entirely until a point in time,
+/// for example when the upstream tells us to back off with a `Retry-After` header.
+pub struct RateLimiter {
+    capacity: f64,
+    refill_per_sec: f64,
+    state: Mutex<RateLimiterState>,
+}
+
+struct RateLimiterState {
+    tokens: f64,
+    last_refill: Instant,
+    blocked_until: Option<Instant>,
+}
+
+impl RateLimiter {
+    /// Allows up to `requests` requests per `period`, refilling continuously.
+    pub fn new(requests: u32, period: Duration) -> Self {
+        let capacity = f64::from(requests);
+        let refill_per_sec = if period.as_secs_f64() > 0.0 {
+            capacity / period.as_secs_f64()
+        } else {
+            f64::INFINITY
+        };
+        Self {
+            capacity,
+            refill_per_sec,
+            state: Mutex::new(RateLimiterState {
+                tokens: capacity,
+                last_refill: Instant::now(),
+                blocked_until: None,
+            }),
+        }
+    }
+
+    /// Never limits requests, unless blocked.
+    pub fn unlimited() -> Self {
+        Self {
+            capacity: f64::INFINITY,
+            refill_per_sec: f64::INFINITY,
+            state: Mutex::new(RateLimiterState {
+                tokens: f64::INFINITY,
+                last_refill: Instant::now(),
+                blocked_until: None,
+            }),
+        }
+    }
+
+    /// Takes a token if one is available, returning false if the request should not be made.
+    pub fn try_acquire(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+
+        match state.blocked_until {
+            Some(until) if now < until => return false,
+            Some(_) => state.blocked_until = None,
+            None => {}
+        }
+
+        let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        state.last_refill = now;
+
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Rejects every request for the given duration.
+    pub fn block_for(&self, duration: Duration) {
+        let until = Instant::now() + duration;
+        let mut state = self.state.lock().unwrap();
+        if state.blocked_until.is_none_or(|current| current < until) {
+            state.blocked_until = Some(until);
+        }
+    }
+
+    /// Throws away every available token, so requests wait for the bucket to refill.
+    pub fn drain(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.tokens.is_finite() {
+            state.tokens = 0.0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_allows_up_to_the_capacity() {
+        // arrange
+        let limiter = RateLimiter::new(2, Duration::from_secs(3600));
+
+        // act & assert
+        assert!(limiter.try_acquire());
+        assert!(limiter.try_acquire());
+        assert!(!limiter.try_acquire());
+    }
+
+    #[test]
+    fn it_refills_over_time() {
+        // arrange
+        let limiter = RateLimiter::new(1, Duration::from_millis(20));
+        assert!(limiter.try_acquire());
+        assert!(!limiter.try_acquire());
+
+        // act
+        std::thread::sleep(Duration::from_millis(30));
+
+        // assert
+        assert!(limiter.try_acquire());
+    }
+
+    #[test]
+    fn it_rejects_everything_while_blocked() {
+        // arrange
+        let limiter = RateLimiter::unlimited();
+
+        // act
+        limiter.block_for(Duration::from_millis(20));
+
+        // assert
+        assert!(!limiter.try_acquire());
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(limiter.try_acquire());
+    }
+
+    #[test]
+    fn it_waits_for_a_refill_after_being_drained() {
+        // arrange
+        let limiter = RateLimiter::new(5, Duration::from_secs(3600));
+
+        // act
+        limiter.drain();
+
+        // assert
+        assert!(!limiter.try_acquire());
+    }
+
+    #[test]
+    fn it_never_limits_when_unlimited() {
+        // arrange
+        let limiter = RateLimiter::unlimited();
+
+        // act & assert
+        for _ in 0..1000 {
+            assert!(limiter.try_acquire());
+        }
+    }
+}
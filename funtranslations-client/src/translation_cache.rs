@@ -0,0 +1,94 @@
+use crate::translation_client::TranslationType;
+use std::path::Path;
+
+/// An on-disk cache of translated text, keyed by the input text and translation type.
+///
+/// Backed by sled, so translations survive restarts. Cloning shares the underlying database.
+#[derive(Clone)]
+pub struct TranslationCache {
+    db: sled::Db,
+}
+
+impl TranslationCache {
+    pub fn open<P: AsRef<Path>>(path: P) -> std::result::Result<Self, sled::Error> {
+        Ok(Self {
+            db: sled::open(path)?,
+        })
+    }
+
+    fn key(text: &str, translation_type: TranslationType) -> Vec<u8> {
+        // The type name can't contain a NUL byte, so this can't collide for different inputs
+        let mut key = translation_type.name().as_bytes().to_vec();
+        key.push(0);
+        key.extend_from_slice(text.as_bytes());
+        key
+    }
+
+    /// Failing to read from the cache is treated as a miss.
+    pub fn get(&self, text: &str, translation_type: TranslationType) -> Option<String> {
+        self.db
+            .get(Self::key(text, translation_type))
+            .ok()
+            .flatten()
+            .and_then(|value| String::from_utf8(value.to_vec()).ok())
+    }
+
+    /// Failing to write to the cache is ignored, as the translation can always be fetched again.
+    pub fn insert(&self, text: &str, translation_type: TranslationType, translated: &str) {
+        let _ = self
+            .db
+            .insert(Self::key(text, translation_type), translated.as_bytes());
+    }
+
+    /// Writes every cached translation to disk. Sled flushes periodically in the background, so
+    /// this is only needed before exiting.
+    pub fn flush(&self) -> std::result::Result<(), sled::Error> {
+        self.db.flush().map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_keys_translations_by_text_and_type() {
+        // arrange
+        let dir = tempfile::tempdir().unwrap();
+        let cache = TranslationCache::open(dir.path()).unwrap();
+
+        // act
+        cache.insert("Hello world", TranslationType::YODA, "world hello");
+
+        // assert
+        assert_eq!(
+            cache.get("Hello world", TranslationType::YODA),
+            Some("world hello".into())
+        );
+        assert_eq!(cache.get("Hello world", TranslationType::SHAKESPEARE), None);
+        assert_eq!(cache.get("Goodbye world", TranslationType::YODA), None);
+    }
+
+    #[test]
+    fn it_persists_translations_across_reopens() {
+        // arrange
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let cache = TranslationCache::open(dir.path()).unwrap();
+            cache.insert(
+                "Hello world",
+                TranslationType::SHAKESPEARE,
+                "Hello world, forsooth",
+            );
+        }
+
+        // act
+        let cache = TranslationCache::open(dir.path()).unwrap();
+
+        // assert
+        assert_eq!(
+            cache.get("Hello world", TranslationType::SHAKESPEARE),
+            Some("Hello world, forsooth".into())
+        );
+    }
+}
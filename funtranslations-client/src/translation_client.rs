@@ -0,0 +1,1325 @@
+use crate::circuit_breaker::{CircuitBreaker, CircuitState};
+use crate::error::{ErrorContext, TranslationError};
+use crate::local_translator::{LocalTranslation, LocalTranslator};
+use crate::observer::{NoopObserver, TranslationObserver};
+use crate::rate_limit::RateLimiter;
+use crate::translation_cache::TranslationCache;
+use http_support::{BuildClientError, SingleFlight, Timeouts};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use surf::http::other::RetryAfter;
+use surf::{Client, StatusCode};
+use tracing::{debug, field, info_span, warn, Instrument, Span};
+
+const API_TOKEN_KEY: &str = "X-Funtranslations-Api-Secret";
+const RETRY_AFTER_KEY: &str = "Retry-After";
+
+/// The body funtranslations responds with.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct TranslationResponse {
+    pub success: TranslationSuccess,
+    pub contents: TranslationTextContents,
+}
+
+impl TranslationResponse {
+    pub fn get_translation(&self) -> std::result::Result<String, TranslationError> {
+        match self.success.total {
+            1 => Ok(self.contents.translated.clone()),
+            total => Err(TranslationError::Api(ErrorContext::default().with_source(
+                format!("funtranslations reported {} successful translations", total),
+            ))),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct TranslationTextContents {
+    pub translated: String,
+    pub text: String,
+    pub translation: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct TranslationSuccess {
+    pub total: i64,
+}
+
+/// One of the translators funtranslations provides.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TranslationType {
+    name: &'static str,
+    display_name: &'static str,
+    path: &'static str,
+}
+
+impl TranslationType {
+    const fn new(name: &'static str, display_name: &'static str, path: &'static str) -> Self {
+        Self {
+            name,
+            display_name,
+            path,
+        }
+    }
+
+    pub const YODA: TranslationType = TranslationType::new("yoda", "Yoda", "translate/yoda.json");
+    pub const SHAKESPEARE: TranslationType =
+        TranslationType::new("shakespeare", "Shakespeare", "translate/shakespeare.json");
+
+    /// Every translator we support, in the order they are listed to users.
+    /// Adding a funtranslations translator only needs a new entry here.
+    pub const ALL: &'static [TranslationType] = &[
+        TranslationType::YODA,
+        TranslationType::SHAKESPEARE,
+        TranslationType::new("pirate", "Pirate", "translate/pirate.json"),
+        TranslationType::new("minion", "Minion", "translate/minion.json"),
+        TranslationType::new("sith", "Sith", "translate/sith.json"),
+        TranslationType::new("pig-latin", "Pig Latin", "translate/pig-latin.json"),
+        TranslationType::new("klingon", "Klingon", "translate/klingon.json"),
+        TranslationType::new("vulcan", "Vulcan", "translate/vulcan.json"),
+        TranslationType::new("romulan", "Romulan", "translate/romulan.json"),
+        TranslationType::new("gungan", "Gungan", "translate/gungan.json"),
+        TranslationType::new("huttese", "Huttese", "translate/huttese.json"),
+        TranslationType::new("mandalorian", "Mandalorian", "translate/mandalorian.json"),
+        TranslationType::new("cheunh", "Cheunh", "translate/cheunh.json"),
+        TranslationType::new("valyrian", "High Valyrian", "translate/valyrian.json"),
+        TranslationType::new("dothraki", "Dothraki", "translate/dothraki.json"),
+        TranslationType::new("sindarin", "Sindarin", "translate/sindarin.json"),
+        TranslationType::new("quenya", "Quenya", "translate/quenya.json"),
+        TranslationType::new("oldenglish", "Old English", "translate/oldenglish.json"),
+        TranslationType::new("groot", "Groot", "translate/groot.json"),
+        TranslationType::new("fudd", "Elmer Fudd", "translate/fudd.json"),
+        TranslationType::new("cockney", "Cockney", "translate/cockney.json"),
+        TranslationType::new("australian", "Australian", "translate/australian.json"),
+        TranslationType::new("leetspeak", "Leetspeak", "translate/leetspeak.json"),
+        TranslationType::new("morse", "Morse code", "translate/morse.json"),
+    ];
+
+    /// Finds a translation type by its name, as returned by `name`.
+    pub fn from_name(name: &str) -> Option<TranslationType> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|translation_type| translation_type.name == name)
+    }
+
+    /// The name used to pick this translation, such as `pig-latin`.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// A human readable name, such as `Pig Latin`.
+    pub fn display_name(&self) -> &'static str {
+        self.display_name
+    }
+
+    fn as_url(&self) -> &'static str {
+        self.path
+    }
+}
+
+/// Where a translation was made.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TranslationSource {
+    /// The funtranslations api, or the cache of its translations
+    Funtranslations,
+    /// The offline translator
+    Offline,
+}
+
+impl TranslationSource {
+    /// The name reported to users, such as `offline`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TranslationSource::Funtranslations => "funtranslations",
+            TranslationSource::Offline => "offline",
+        }
+    }
+}
+
+/// Translated text, and where it was translated.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Translation {
+    pub text: String,
+    pub source: TranslationSource,
+}
+
+/// Translates text with funtranslations. Clones share the rate limit, circuit breaker and cache.
+#[derive(Clone)]
+pub struct TranslationClient {
+    base_url: String,
+    api_token: Option<String>,
+    client: Client, // Surfs clone implementation shares the underlying HttpClient
+    cache: Option<TranslationCache>,
+    in_flight:
+        Arc<SingleFlight<(String, TranslationType), std::result::Result<String, TranslationError>>>,
+    rate_limiter: Arc<RateLimiter>,
+    circuit_breaker: Arc<CircuitBreaker>,
+    max_retry_after: Duration,
+    timeouts: Timeouts,
+    local_translator: LocalTranslator,
+    local_translation: LocalTranslation,
+    observer: Arc<dyn TranslationObserver>,
+}
+
+impl TranslationClient {
+    /// A client for the funtranslations api at `base_url`, such as `https://api.funtranslations.com`.
+    /// The `api_token` is sent with every request when set.
+    pub fn new(base_url: String, api_token: Option<String>) -> Self {
+        let timeouts = Timeouts::default();
+        Self {
+            base_url,
+            api_token,
+            // Client::new panics when the http client can't be built too
+            client: timeouts
+                .build_client()
+                .expect("Failed to build the http client"),
+            cache: None,
+            in_flight: Arc::new(SingleFlight::new()),
+            rate_limiter: Arc::new(RateLimiter::unlimited()),
+            circuit_breaker: Arc::new(CircuitBreaker::new(5, Duration::from_secs(30))),
+            max_retry_after: Duration::from_secs(60),
+            timeouts,
+            local_translator: LocalTranslator,
+            local_translation: LocalTranslation::Disabled,
+            observer: Arc::new(NoopObserver),
+        }
+    }
+
+    /// Gives up on translation requests once a timeout is hit.
+    pub fn with_timeouts(mut self, timeouts: Timeouts) -> Result<Self, BuildClientError> {
+        self.client = timeouts.build_client()?;
+        self.timeouts = timeouts;
+        Ok(self)
+    }
+
+    /// Stops calling funtranslations after `failure_threshold` consecutive failures,
+    /// until `cool_down` has passed.
+    pub fn with_circuit_breaker(mut self, failure_threshold: u32, cool_down: Duration) -> Self {
+        self.circuit_breaker = Arc::new(CircuitBreaker::new(failure_threshold, cool_down));
+        self
+    }
+
+    /// The state of the circuit breaker guarding funtranslations.
+    pub fn circuit_state(&self) -> CircuitState {
+        self.circuit_breaker.state()
+    }
+
+    /// Checks funtranslations can be reached. Translations are rate limited, so this requests the
+    /// base url without translating anything, and only fails on server errors, connection errors
+    /// and timeouts. The circuit breaker, rate limit and observer aren't involved.
+    pub async fn check_health(&self) -> std::result::Result<(), TranslationError> {
+        let request = self.client.get(&self.base_url);
+        let response = tokio::time::timeout(self.timeouts.total, request)
+            .await
+            .map_err(|_| TranslationError::Timeout)?
+            .map_err(|err| {
+                if Timeouts::is_timeout(&err) {
+                    TranslationError::Timeout
+                } else {
+                    TranslationError::Api(ErrorContext::default().with_source(err.into_inner()))
+                }
+            })?;
+        if response.status().is_server_error() {
+            return Err(TranslationError::Api(
+                ErrorContext::default().with_status(response.status().into()),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Limits requests to funtranslations to `requests` per `period`.
+    /// Requests over the limit fail locally with `TranslationError::TooManyRequests`.
+    pub fn with_rate_limit(mut self, requests: u32, period: Duration) -> Self {
+        self.rate_limiter = Arc::new(RateLimiter::new(requests, period));
+        self
+    }
+
+    /// Caps how long a `Retry-After` from funtranslations can block translations for,
+    /// so a bad header can't block them until the process restarts.
+    pub fn with_max_retry_after(mut self, max_retry_after: Duration) -> Self {
+        self.max_retry_after = max_retry_after;
+        self
+    }
+
+    /// Stores successful translations in the given cache, so the same text is only translated once.
+    pub fn with_cache(mut self, cache: TranslationCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Translates offline instead of, or when failing to, call funtranslations.
+    /// Offline translations aren't cached, so funtranslations gets used again once it recovers.
+    pub fn with_local_translation(mut self, local_translation: LocalTranslation) -> Self {
+        self.local_translation = local_translation;
+        self
+    }
+
+    /// Reports requests and cache lookups to the given observer.
+    pub fn with_observer(mut self, observer: Arc<dyn TranslationObserver>) -> Self {
+        self.observer = observer;
+        self
+    }
+
+    async fn get_translation_response(
+        &self,
+        text: &str,
+        translation_type: TranslationType,
+        headers: &[(&str, String)],
+    ) -> std::result::Result<TranslationResponse, TranslationError> {
+        if !self.circuit_breaker.try_acquire() {
+            debug!("Skipping translation request, the circuit breaker is open");
+            return Err(TranslationError::CircuitOpen);
+        }
+
+        // Don't spend a request we know is going to be rejected
+        if !self.rate_limiter.try_acquire() {
+            self.circuit_breaker.release();
+            debug!("Skipping translation request, the rate limit has been reached");
+            return Err(TranslationError::TooManyRequests);
+        }
+
+        let span = info_span!(
+            "funtranslations_request",
+            status = field::Empty,
+            duration_ms = field::Empty
+        );
+        let start = Instant::now();
+        let response = tokio::time::timeout(
+            self.timeouts.total,
+            self.send_translation_request(text, translation_type, headers),
+        )
+        .instrument(span.clone())
+        .await
+        .unwrap_or_else(|_| {
+            span.in_scope(|| warn!("Translation request timed out"));
+            Err(TranslationError::Timeout)
+        });
+        let duration = start.elapsed();
+        span.record("duration_ms", duration.as_millis() as u64);
+        self.observer.on_request(duration, response.as_ref().err());
+        match &response {
+            // Being rate limited means funtranslations is still up
+            Ok(_) | Err(TranslationError::TooManyRequests) => self.circuit_breaker.record_success(),
+            Err(_) => self.circuit_breaker.record_failure(),
+        }
+        response
+    }
+
+    async fn send_translation_request(
+        &self,
+        text: &str,
+        translation_type: TranslationType,
+        headers: &[(&str, String)],
+    ) -> std::result::Result<TranslationResponse, TranslationError> {
+        let request_body = json!({ "text": text });
+
+        let url = format!("{}/{}", self.base_url, translation_type.as_url());
+
+        let mut request = surf::post(url).body(request_body).build();
+        if let Some(token) = &self.api_token {
+            request.insert_header(API_TOKEN_KEY, token.as_str());
+        }
+        for (name, value) in headers {
+            request.insert_header(*name, value.as_str());
+        }
+
+        let mut response = self.client.send(request).await.map_err(|err| {
+            warn!(error = %err, "Failed to send translation request");
+            if Timeouts::is_timeout(&err) {
+                TranslationError::Timeout
+            } else {
+                TranslationError::Api(ErrorContext::default().with_source(err.into_inner()))
+            }
+        })?;
+
+        let status = response.status();
+        Span::current().record("status", u16::from(status));
+        let context = ErrorContext::default().with_status(status.into());
+        match status {
+            StatusCode::Ok => {
+                let body = self.read_body(&mut response, &context).await?;
+                serde_json::from_str(&body).map_err(|err| {
+                    warn!(error = %err, "Failed to deserialize translation response");
+                    TranslationError::Deserialization(context.with_source(err).with_body(&body))
+                })
+            }
+            StatusCode::TooManyRequests => {
+                let retry_after =
+                    retry_after(&response).map(|duration| duration.min(self.max_retry_after));
+                match retry_after {
+                    Some(duration) => self.rate_limiter.block_for(duration),
+                    None => self.rate_limiter.drain(),
+                }
+                warn!(?retry_after, "Funtranslations rate limit reached");
+                Err(TranslationError::TooManyRequests)
+            }
+            status => {
+                warn!(%status, "Funtranslations responded with an unexpected status");
+                let body = self.read_body(&mut response, &context).await?;
+                Err(TranslationError::Api(context.with_body(&body)))
+            }
+        }
+    }
+
+    async fn read_body(
+        &self,
+        response: &mut surf::Response,
+        context: &ErrorContext,
+    ) -> std::result::Result<String, TranslationError> {
+        response.body_string().await.map_err(|err| {
+            warn!(error = %err, "Failed to read translation response");
+            TranslationError::Api(context.clone().with_source(err.into_inner()))
+        })
+    }
+
+    /// Translates `text`, from the cache if it has been translated before.
+    pub async fn get_translation(
+        &self,
+        text: &str,
+        translation_type: TranslationType,
+    ) -> std::result::Result<String, TranslationError> {
+        self.get_translation_with_headers(text, translation_type, &[])
+            .await
+    }
+
+    /// Translates `text` like `get_translation`, sending `headers` on the request to
+    /// funtranslations, such as a request id or trace context. Translations coalesced with one
+    /// already in flight only send the headers of the first.
+    pub async fn get_translation_with_headers(
+        &self,
+        text: &str,
+        translation_type: TranslationType,
+        headers: &[(&str, String)],
+    ) -> std::result::Result<String, TranslationError> {
+        self.translate(text, translation_type, headers)
+            .await
+            .map(|translation| translation.text)
+    }
+
+    /// Translates `text` like `get_translation_with_headers`, also reporting whether it was
+    /// translated offline.
+    #[tracing::instrument(
+        name = "get_translation",
+        skip(self, text, headers),
+        fields(translation = translation_type.name(), text_length = text.len())
+    )]
+    pub async fn translate(
+        &self,
+        text: &str,
+        translation_type: TranslationType,
+        headers: &[(&str, String)],
+    ) -> std::result::Result<Translation, TranslationError> {
+        let local_supported = self.local_translator.supports(translation_type);
+        if self.local_translation == LocalTranslation::Primary && local_supported {
+            return self.get_local_translation(text, translation_type).await;
+        }
+
+        match self
+            .get_remote_translation(text, translation_type, headers)
+            .await
+        {
+            Ok(text) => Ok(Translation {
+                text,
+                source: TranslationSource::Funtranslations,
+            }),
+            Err(error)
+                if self.local_translation == LocalTranslation::Fallback && local_supported =>
+            {
+                warn!(error = %error, "Translating offline, funtranslations failed");
+                self.get_local_translation(text, translation_type).await
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    async fn get_local_translation(
+        &self,
+        text: &str,
+        translation_type: TranslationType,
+    ) -> std::result::Result<Translation, TranslationError> {
+        let text = self
+            .local_translator
+            .get_translation(text, translation_type)
+            .await?;
+        Ok(Translation {
+            text,
+            source: TranslationSource::Offline,
+        })
+    }
+
+    async fn get_remote_translation(
+        &self,
+        text: &str,
+        translation_type: TranslationType,
+        headers: &[(&str, String)],
+    ) -> std::result::Result<String, TranslationError> {
+        if let Some(cache) = &self.cache {
+            let cached = cache.get(text, translation_type);
+            self.observer.on_cache_lookup(cached.is_some());
+            if let Some(translated) = cached {
+                return Ok(translated);
+            }
+        }
+
+        // Concurrent translations of the same text share a single upstream request
+        self.in_flight
+            .run((text.to_string(), translation_type), || async {
+                let response = self
+                    .get_translation_response(text, translation_type, headers)
+                    .await?;
+                let translated = response.get_translation()?;
+
+                if let Some(cache) = &self.cache {
+                    cache.insert(text, translation_type, &translated);
+                }
+                Ok(translated)
+            })
+            .await
+    }
+}
+
+/// How long funtranslations asked us to wait, from a `Retry-After` header in either delta-seconds
+/// or HTTP-date form. Delta-seconds too large to represent saturate instead of overflowing.
+fn retry_after(response: &surf::Response) -> Option<Duration> {
+    let value = response.header(RETRY_AFTER_KEY)?.last().as_str().trim();
+    if !value.is_empty() && value.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Some(Duration::from_secs(value.parse().unwrap_or(u64::MAX)));
+    }
+    RetryAfter::from_headers(response)
+        .ok()
+        .flatten()
+        .and_then(|retry_after| retry_after.duration_since(SystemTime::now()).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{body_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct TextInput {
+        text: String,
+    }
+
+    #[tokio::test]
+    async fn it_returns_an_api_error_on_500_response() {
+        // arrange
+        let expected_text = TextInput {
+            text: "Hello world".into(),
+        };
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_json(expected_text))
+            .and(path("translate/yoda.json"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&mock_server)
+            .await;
+
+        let client = TranslationClient::new(mock_server.uri(), None);
+
+        // act
+        let response = client
+            .get_translation("Hello world", TranslationType::YODA)
+            .await;
+
+        // assert
+        if let Err(err) = response {
+            assert_eq!(err, TranslationError::Api(ErrorContext::default()));
+        } else {
+            unreachable!();
+        }
+    }
+
+    #[tokio::test]
+    async fn it_errors_on_invalid_data() {
+        // arrange
+        let expected_text = TextInput {
+            text: "Hello world".into(),
+        };
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_json(expected_text))
+            .and(path("translate/yoda.json"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&mock_server)
+            .await;
+
+        let client = TranslationClient::new(mock_server.uri(), None);
+
+        // act
+        let response = client
+            .get_translation("Hello world", TranslationType::YODA)
+            .await;
+
+        // assert
+        if let Err(err) = response {
+            assert_eq!(
+                err,
+                TranslationError::Deserialization(ErrorContext::default())
+            );
+        } else {
+            unreachable!();
+        }
+    }
+
+    #[tokio::test]
+    async fn it_successfully_deserializes_a_response() {
+        // arrange
+        let expected_text = TextInput {
+            text: "Hello world".into(),
+        };
+
+        let expected_body = TranslationResponse {
+            success: TranslationSuccess { total: 1 },
+            contents: TranslationTextContents {
+                translated: "world hello".into(),
+                text: "hello world".into(),
+                translation: "shakespeare".into(),
+            },
+        };
+        let mock_response = ResponseTemplate::new(200).set_body_json(json!(expected_body));
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_json(expected_text))
+            .and(path("translate/shakespeare.json"))
+            .respond_with(mock_response)
+            .mount(&mock_server)
+            .await;
+
+        let client = TranslationClient::new(mock_server.uri(), None);
+
+        // act
+        let response = client
+            .get_translation_response("Hello world", TranslationType::SHAKESPEARE, &[])
+            .await
+            .unwrap();
+
+        // assert
+        assert_eq!(response, expected_body);
+    }
+
+    #[tokio::test]
+    async fn it_successfully_sends_an_api_key() {
+        // arrange
+        let api_token = String::from("an_api_token");
+        let expected_text = TextInput {
+            text: "Hello world".into(),
+        };
+
+        let expected_body = TranslationResponse {
+            success: TranslationSuccess { total: 1 },
+            contents: TranslationTextContents {
+                translated: "world hello".into(),
+                text: "hello world".into(),
+                translation: "shakespeare".into(),
+            },
+        };
+
+        let mock_response = ResponseTemplate::new(200).set_body_json(json!(expected_body));
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_json(expected_text))
+            .and(path("translate/shakespeare.json"))
+            .and(header(API_TOKEN_KEY, api_token.as_str()))
+            .respond_with(mock_response)
+            .mount(&mock_server)
+            .await;
+
+        let client = TranslationClient::new(mock_server.uri(), Some(api_token));
+
+        // act
+        let response = client
+            .get_translation_response("Hello world", TranslationType::SHAKESPEARE, &[])
+            .await
+            .unwrap();
+
+        // assert
+        assert_eq!(response, expected_body);
+    }
+
+    #[tokio::test]
+    async fn it_successfully_gets_translation() {
+        // arrange
+        let expected_text = TextInput {
+            text: "Hello world".into(),
+        };
+
+        let expected_body = TranslationResponse {
+            success: TranslationSuccess { total: 1 },
+            contents: TranslationTextContents {
+                translated: "world hello".into(),
+                text: "hello world".into(),
+                translation: "shakespeare".into(),
+            },
+        };
+        let mock_response = ResponseTemplate::new(200).set_body_json(json!(expected_body));
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_json(expected_text))
+            .and(path("translate/yoda.json"))
+            .respond_with(mock_response)
+            .mount(&mock_server)
+            .await;
+
+        let client = TranslationClient::new(mock_server.uri(), None);
+
+        //act
+        let response = client
+            .get_translation("Hello world", TranslationType::YODA)
+            .await
+            .unwrap();
+
+        // assert
+        assert_eq!(response, "world hello");
+    }
+
+    #[tokio::test]
+    async fn it_only_translates_cached_text_once() {
+        // arrange
+        let expected_text = TextInput {
+            text: "Hello world".into(),
+        };
+
+        let expected_body = TranslationResponse {
+            success: TranslationSuccess { total: 1 },
+            contents: TranslationTextContents {
+                translated: "world hello".into(),
+                text: "hello world".into(),
+                translation: "yoda".into(),
+            },
+        };
+        let mock_response = ResponseTemplate::new(200).set_body_json(json!(expected_body));
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_json(expected_text))
+            .and(path("translate/yoda.json"))
+            .respond_with(mock_response)
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let cache_dir = tempfile::tempdir().unwrap();
+        let client = TranslationClient::new(mock_server.uri(), None)
+            .with_cache(TranslationCache::open(cache_dir.path()).unwrap());
+
+        // act
+        for _ in 0..3 {
+            let response = client
+                .get_translation("Hello world", TranslationType::YODA)
+                .await
+                .unwrap();
+            assert_eq!(response, "world hello");
+        }
+
+        // assert
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn it_coalesces_concurrent_translations_of_the_same_text() {
+        // arrange
+        let expected_text = TextInput {
+            text: "Hello world".into(),
+        };
+
+        let expected_body = TranslationResponse {
+            success: TranslationSuccess { total: 1 },
+            contents: TranslationTextContents {
+                translated: "world hello".into(),
+                text: "hello world".into(),
+                translation: "yoda".into(),
+            },
+        };
+        let mock_response = ResponseTemplate::new(200)
+            .set_body_json(json!(expected_body))
+            .set_delay(std::time::Duration::from_millis(200));
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_json(expected_text))
+            .and(path("translate/yoda.json"))
+            .respond_with(mock_response)
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = TranslationClient::new(mock_server.uri(), None);
+
+        // act
+        let handles: Vec<_> = (0..10)
+            .map(|_| {
+                let client = client.clone();
+                tokio::spawn(async move {
+                    client
+                        .get_translation("Hello world", TranslationType::YODA)
+                        .await
+                })
+            })
+            .collect();
+
+        // assert
+        for handle in handles {
+            assert_eq!(handle.await.unwrap(), Ok("world hello".into()));
+        }
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn it_short_circuits_requests_over_the_rate_limit() {
+        // arrange
+        let expected_body = TranslationResponse {
+            success: TranslationSuccess { total: 1 },
+            contents: TranslationTextContents {
+                translated: "world hello".into(),
+                text: "hello world".into(),
+                translation: "yoda".into(),
+            },
+        };
+        let mock_response = ResponseTemplate::new(200).set_body_json(json!(expected_body));
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("translate/yoda.json"))
+            .respond_with(mock_response)
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = TranslationClient::new(mock_server.uri(), None)
+            .with_rate_limit(1, Duration::from_secs(3600));
+
+        // act
+        let first = client
+            .get_translation("Hello world", TranslationType::YODA)
+            .await;
+        let second = client
+            .get_translation("Goodbye world", TranslationType::YODA)
+            .await;
+
+        // assert
+        assert_eq!(first, Ok("world hello".into()));
+        assert_eq!(second, Err(TranslationError::TooManyRequests));
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn it_honors_retry_after_on_429() {
+        // arrange
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("translate/yoda.json"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "3600"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = TranslationClient::new(mock_server.uri(), None);
+
+        // act
+        let first = client
+            .get_translation("Hello world", TranslationType::YODA)
+            .await;
+        let second = client
+            .get_translation("Goodbye world", TranslationType::YODA)
+            .await;
+
+        // assert
+        assert_eq!(first, Err(TranslationError::TooManyRequests));
+        assert_eq!(second, Err(TranslationError::TooManyRequests));
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn it_caps_a_huge_retry_after() {
+        // arrange
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("translate/yoda.json"))
+            .respond_with(
+                ResponseTemplate::new(429)
+                    .insert_header("Retry-After", u64::MAX.to_string().as_str()),
+            )
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let client = TranslationClient::new(mock_server.uri(), None)
+            .with_max_retry_after(Duration::from_millis(50));
+
+        // act
+        let first = client
+            .get_translation("Hello world", TranslationType::YODA)
+            .await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let second = client
+            .get_translation("Goodbye world", TranslationType::YODA)
+            .await;
+
+        // assert
+        assert_eq!(first, Err(TranslationError::TooManyRequests));
+        assert_eq!(second, Err(TranslationError::TooManyRequests));
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn it_retries_once_retry_after_has_passed() {
+        // arrange
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("translate/yoda.json"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let client = TranslationClient::new(mock_server.uri(), None);
+
+        // act
+        let first = client
+            .get_translation("Hello world", TranslationType::YODA)
+            .await;
+        let second = client
+            .get_translation("Goodbye world", TranslationType::YODA)
+            .await;
+
+        // assert
+        assert_eq!(first, Err(TranslationError::TooManyRequests));
+        assert_eq!(second, Err(TranslationError::TooManyRequests));
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn it_trips_the_circuit_breaker_and_recovers() {
+        // arrange
+        let expected_body = TranslationResponse {
+            success: TranslationSuccess { total: 1 },
+            contents: TranslationTextContents {
+                translated: "world hello".into(),
+                text: "hello world".into(),
+                translation: "yoda".into(),
+            },
+        };
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("translate/yoda.json"))
+            .respond_with(ResponseTemplate::new(500))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("translate/yoda.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(expected_body)))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = TranslationClient::new(mock_server.uri(), None)
+            .with_circuit_breaker(2, Duration::from_millis(100));
+
+        // act & assert
+        for _ in 0..2 {
+            let response = client
+                .get_translation("Hello world", TranslationType::YODA)
+                .await;
+            assert_eq!(
+                response,
+                Err(TranslationError::Api(ErrorContext::default()))
+            );
+        }
+        assert_eq!(client.circuit_state(), CircuitState::Open);
+
+        let response = client
+            .get_translation("Hello world", TranslationType::YODA)
+            .await;
+        assert_eq!(response, Err(TranslationError::CircuitOpen));
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert_eq!(client.circuit_state(), CircuitState::HalfOpen);
+
+        let response = client
+            .get_translation("Hello world", TranslationType::YODA)
+            .await;
+        assert_eq!(response, Ok("world hello".into()));
+        assert_eq!(client.circuit_state(), CircuitState::Closed);
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn it_does_not_trip_the_circuit_breaker_when_rate_limited() {
+        // arrange
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("translate/yoda.json"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+            .mount(&mock_server)
+            .await;
+
+        let client = TranslationClient::new(mock_server.uri(), None)
+            .with_circuit_breaker(1, Duration::from_secs(60));
+
+        // act
+        let response = client
+            .get_translation("Hello world", TranslationType::YODA)
+            .await;
+
+        // assert
+        assert_eq!(response, Err(TranslationError::TooManyRequests));
+        assert_eq!(client.circuit_state(), CircuitState::Closed);
+    }
+
+    #[tokio::test]
+    async fn it_times_out_on_a_slow_response() {
+        // arrange
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("translate/yoda.json"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(5)))
+            .mount(&mock_server)
+            .await;
+
+        let client = TranslationClient::new(mock_server.uri(), None)
+            .with_timeouts(Timeouts {
+                connect: Duration::from_secs(1),
+                total: Duration::from_millis(100),
+            })
+            .unwrap();
+
+        // act
+        let response = client
+            .get_translation("Hello world", TranslationType::YODA)
+            .await;
+
+        // assert
+        assert_eq!(response, Err(TranslationError::Timeout));
+    }
+
+    #[tokio::test]
+    async fn it_uses_the_path_for_the_translation_type() {
+        // arrange
+        let expected_body = TranslationResponse {
+            success: TranslationSuccess { total: 1 },
+            contents: TranslationTextContents {
+                translated: "Ahoy world".into(),
+                text: "Hello world".into(),
+                translation: "pirate".into(),
+            },
+        };
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("translate/pirate.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(expected_body)))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = TranslationClient::new(mock_server.uri(), None);
+
+        // act
+        let response = client
+            .get_translation("Hello world", TranslationType::from_name("pirate").unwrap())
+            .await;
+
+        // assert
+        assert_eq!(response, Ok("Ahoy world".into()));
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn it_falls_back_to_translating_offline() {
+        // arrange
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("translate/shakespeare.json"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = TranslationClient::new(mock_server.uri(), None)
+            .with_local_translation(LocalTranslation::Fallback);
+
+        // act
+        let response = client
+            .translate("Is that enough?", TranslationType::SHAKESPEARE, &[])
+            .await;
+
+        // assert
+        assert_eq!(
+            response,
+            Ok(Translation {
+                text: "Is yond enow?".into(),
+                source: TranslationSource::Offline,
+            })
+        );
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn it_does_not_fall_back_for_styles_that_are_not_supported_offline() {
+        // arrange
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("translate/pirate.json"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&mock_server)
+            .await;
+
+        let client = TranslationClient::new(mock_server.uri(), None)
+            .with_local_translation(LocalTranslation::Fallback);
+
+        // act
+        let response = client
+            .get_translation("Hello world", TranslationType::from_name("pirate").unwrap())
+            .await;
+
+        // assert
+        assert_eq!(
+            response,
+            Err(TranslationError::Api(ErrorContext::default()))
+        );
+    }
+
+    #[tokio::test]
+    async fn it_only_translates_offline_when_local_is_primary() {
+        // arrange
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        let client = TranslationClient::new(mock_server.uri(), None)
+            .with_local_translation(LocalTranslation::Primary);
+
+        // act
+        let response = client
+            .translate("Hello world", TranslationType::SHAKESPEARE, &[])
+            .await;
+
+        // assert
+        assert_eq!(
+            response,
+            Ok(Translation {
+                text: "Hail world".into(),
+                source: TranslationSource::Offline,
+            })
+        );
+        mock_server.verify().await;
+    }
+
+    #[test]
+    fn it_finds_translation_types_by_name() {
+        // arrange
+        let cases = vec![
+            ("yoda", Some("translate/yoda.json")),
+            ("pig-latin", Some("translate/pig-latin.json")),
+            ("parseltongue", None),
+        ];
+
+        for (name, expected_url) in cases {
+            // act
+            let translation_type = TranslationType::from_name(name);
+
+            // assert
+            assert_eq!(
+                translation_type.map(|t| t.as_url()),
+                expected_url,
+                "{}",
+                name
+            );
+            if let Some(translation_type) = translation_type {
+                assert_eq!(translation_type.name(), name);
+            }
+        }
+    }
+
+    #[test]
+    fn it_has_unique_translation_names() {
+        for (index, translation_type) in TranslationType::ALL.iter().enumerate() {
+            assert!(!TranslationType::ALL[index + 1..]
+                .iter()
+                .any(|other| other.name() == translation_type.name()));
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingObserver {
+        requests: std::sync::Mutex<Vec<Option<TranslationError>>>,
+        cache_lookups: std::sync::Mutex<Vec<bool>>,
+    }
+
+    impl TranslationObserver for RecordingObserver {
+        fn on_request(&self, _duration: Duration, error: Option<&TranslationError>) {
+            self.requests.lock().unwrap().push(error.cloned());
+        }
+
+        fn on_cache_lookup(&self, hit: bool) {
+            self.cache_lookups.lock().unwrap().push(hit);
+        }
+    }
+
+    #[tokio::test]
+    async fn it_reports_sent_requests_and_cache_lookups_to_the_observer() {
+        // arrange
+        let expected_body = TranslationResponse {
+            success: TranslationSuccess { total: 1 },
+            contents: TranslationTextContents {
+                translated: "world hello".into(),
+                text: "hello world".into(),
+                translation: "yoda".into(),
+            },
+        };
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("translate/yoda.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(expected_body)))
+            .mount(&mock_server)
+            .await;
+
+        let cache_dir = tempfile::tempdir().unwrap();
+        let observer = Arc::new(RecordingObserver::default());
+        let client = TranslationClient::new(mock_server.uri(), None)
+            .with_cache(TranslationCache::open(cache_dir.path()).unwrap())
+            .with_rate_limit(1, Duration::from_secs(60))
+            .with_observer(observer.clone());
+
+        // act
+        for text in &["Hello world", "Hello world", "Goodbye world"] {
+            let _ = client.get_translation(text, TranslationType::YODA).await;
+        }
+
+        // assert
+        // The last translation is rejected by the rate limit, so it's never sent
+        assert_eq!(*observer.requests.lock().unwrap(), vec![None]);
+        assert_eq!(
+            *observer.cache_lookups.lock().unwrap(),
+            vec![false, true, false]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_keeps_the_status_and_body_of_failed_requests() {
+        // arrange
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("translate/yoda.json"))
+            .respond_with(
+                ResponseTemplate::new(503).set_body_string("{\"error\": \"Down for maintenance\"}"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let client = TranslationClient::new(mock_server.uri(), None);
+
+        // act
+        let response = client
+            .get_translation("Hello world", TranslationType::YODA)
+            .await;
+
+        // assert
+        if let Err(TranslationError::Api(context)) = response {
+            assert_eq!(context.status(), Some(503));
+            assert_eq!(
+                context.body(),
+                Some("{\"error\": \"Down for maintenance\"}")
+            );
+        } else {
+            unreachable!();
+        }
+    }
+
+    #[tokio::test]
+    async fn it_forwards_the_given_headers() {
+        // arrange
+        let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("translate/yoda.json"))
+            .and(header("x-request-id", "abc-123"))
+            .and(header("traceparent", traceparent))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "success": { "total": 1 },
+                "contents": {
+                    "translated": "Hello world, yoda says",
+                    "text": "Hello world",
+                    "translation": "yoda"
+                }
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = TranslationClient::new(mock_server.uri(), None);
+
+        // act
+        let response = client
+            .get_translation_with_headers(
+                "Hello world",
+                TranslationType::YODA,
+                &[
+                    ("x-request-id", "abc-123".into()),
+                    ("traceparent", traceparent.into()),
+                ],
+            )
+            .await;
+
+        // assert
+        assert_eq!(response, Ok("Hello world, yoda says".into()));
+    }
+
+    #[tokio::test]
+    async fn it_is_healthy_when_funtranslations_responds() {
+        // arrange
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = TranslationClient::new(mock_server.uri(), None)
+            .with_rate_limit(0, Duration::from_secs(60));
+
+        // act
+        let health = client.check_health().await;
+
+        // assert
+        assert_eq!(health, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn it_is_unhealthy_when_funtranslations_fails() {
+        // arrange
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&mock_server)
+            .await;
+
+        let client = TranslationClient::new(mock_server.uri(), None);
+
+        // act
+        let health = client.check_health().await;
+
+        // assert
+        if let Err(TranslationError::Api(context)) = health {
+            assert_eq!(context.status(), Some(503));
+        } else {
+            unreachable!();
+        }
+        assert_eq!(client.circuit_state(), CircuitState::Closed);
+    }
+}
@@ -0,0 +1,121 @@
+use std::error::Error as StdError;
+use std::fmt;
+use std::sync::Arc;
+
+/// The most of an upstream response body kept for error messages.
+const MAX_BODY_CHARS: usize = 200;
+
+/// What went wrong talking to an upstream: the status it responded with, the start of the body and
+/// the underlying error, whichever are known.
+#[derive(Debug, Clone, Default)]
+pub struct ErrorContext {
+    status: Option<u16>,
+    body: Option<String>,
+    source: Option<Arc<dyn StdError + Send + Sync>>,
+}
+
+impl ErrorContext {
+    pub fn with_status(mut self, status: u16) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Keeps the first `MAX_BODY_CHARS` characters of a non empty body.
+    pub fn with_body(mut self, body: &str) -> Self {
+        let body = body.trim();
+        if !body.is_empty() {
+            let mut snippet: String = body.chars().take(MAX_BODY_CHARS).collect();
+            if snippet.len() < body.len() {
+                snippet.push_str("...");
+            }
+            self.body = Some(snippet);
+        }
+        self
+    }
+
+    pub fn with_source(mut self, source: impl Into<Box<dyn StdError + Send + Sync>>) -> Self {
+        self.source = Some(Arc::from(source.into()));
+        self
+    }
+
+    /// The status the upstream responded with.
+    pub fn status(&self) -> Option<u16> {
+        self.status
+    }
+
+    /// The start of the body the upstream responded with.
+    pub fn body(&self) -> Option<&str> {
+        self.body.as_deref()
+    }
+
+    /// The error that caused the failure, such as a connection or deserialization error.
+    pub fn source(&self) -> Option<&(dyn StdError + Send + Sync + 'static)> {
+        self.source.as_deref()
+    }
+}
+
+/// Written after the error message, so it is empty when nothing is known.
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut separator = ": ";
+        if let Some(status) = self.status {
+            write!(f, "{}status {}", separator, status)?;
+            separator = ", ";
+        }
+        if let Some(source) = &self.source {
+            write!(f, "{}{}", separator, source)?;
+            separator = ", ";
+        }
+        if let Some(body) = &self.body {
+            write!(f, "{}body {:?}", separator, body)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_describes_the_context() {
+        // arrange
+        let context = ErrorContext::default()
+            .with_status(200)
+            .with_source("missing field `id`")
+            .with_body(" {\"name\": \"charizard\"}\n");
+
+        // act
+        let message = context.to_string();
+
+        // assert
+        assert_eq!(
+            message,
+            ": status 200, missing field `id`, body \"{\\\"name\\\": \\\"charizard\\\"}\""
+        );
+    }
+
+    #[test]
+    fn it_is_empty_without_context() {
+        // act
+        let message = ErrorContext::default().to_string();
+
+        // assert
+        assert_eq!(message, "");
+    }
+
+    #[test]
+    fn it_truncates_long_bodies() {
+        // arrange
+        let body = "é".repeat(MAX_BODY_CHARS + 1);
+
+        // act
+        let context = ErrorContext::default().with_body(&body);
+
+        // assert
+        assert_eq!(
+            context.body(),
+            Some(format!("{}...", "é".repeat(MAX_BODY_CHARS)).as_str())
+        );
+    }
+}
@@ -0,0 +1,11 @@
+//! Internal helpers shared by the `pokeapi-client` and `funtranslations-client` crates.
+//!
+//! The client crates re-export the public types, so users shouldn't need to depend on this crate.
+
+mod error_context;
+mod single_flight;
+mod timeouts;
+
+pub use error_context::ErrorContext;
+pub use single_flight::SingleFlight;
+pub use timeouts::{BuildClientError, Timeouts};
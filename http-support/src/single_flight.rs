@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+
+/// Coalesces concurrent calls for the same key, so only one of them does the work
+/// and every caller receives a clone of its result.
+pub struct SingleFlight<K, V> {
+    in_flight: Mutex<HashMap<K, broadcast::Sender<V>>>,
+}
+
+impl<K, V> SingleFlight<K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn run<F, Fut>(&self, key: K, f: F) -> V
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = V>,
+    {
+        loop {
+            let mut receiver = {
+                let mut in_flight = self.in_flight.lock().unwrap();
+                match in_flight.get(&key) {
+                    Some(sender) => sender.subscribe(),
+                    None => {
+                        let (sender, _) = broadcast::channel(1);
+                        in_flight.insert(key.clone(), sender);
+                        break;
+                    }
+                }
+            };
+
+            match receiver.recv().await {
+                Ok(value) => return value,
+                // The leading call was dropped before finishing, so try to take over from it
+                Err(_) => continue,
+            }
+        }
+
+        let mut guard = InFlightGuard {
+            in_flight: &self.in_flight,
+            key: Some(key),
+        };
+        let value = f().await;
+        if let Some(sender) = guard.finish() {
+            let _ = sender.send(value.clone());
+        }
+        value
+    }
+}
+
+impl<K, V> Default for SingleFlight<K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Removes the in-flight entry even if the leading call is cancelled, so waiters don't hang.
+struct InFlightGuard<'a, K: Eq + Hash, V> {
+    in_flight: &'a Mutex<HashMap<K, broadcast::Sender<V>>>,
+    key: Option<K>,
+}
+
+impl<'a, K: Eq + Hash, V> InFlightGuard<'a, K, V> {
+    fn finish(&mut self) -> Option<broadcast::Sender<V>> {
+        let key = self.key.take()?;
+        self.in_flight.lock().unwrap().remove(&key)
+    }
+}
+
+impl<'a, K: Eq + Hash, V> Drop for InFlightGuard<'a, K, V> {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn it_runs_concurrent_calls_for_the_same_key_once() {
+        // arrange
+        let single_flight = Arc::new(SingleFlight::new());
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        // act
+        let handles: Vec<_> = (0..10)
+            .map(|_| {
+                let single_flight = single_flight.clone();
+                let calls = calls.clone();
+                tokio::spawn(async move {
+                    single_flight
+                        .run("charizard", || async move {
+                            calls.fetch_add(1, Ordering::SeqCst);
+                            tokio::time::sleep(Duration::from_millis(50)).await;
+                            6
+                        })
+                        .await
+                })
+            })
+            .collect();
+
+        // assert
+        for handle in handles {
+            assert_eq!(handle.await.unwrap(), 6);
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn it_runs_sequential_calls_each_time() {
+        // arrange
+        let single_flight = SingleFlight::new();
+        let calls = AtomicUsize::new(0);
+
+        // act
+        for _ in 0..3 {
+            single_flight
+                .run("charizard", || async {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                })
+                .await;
+        }
+
+        // assert
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn it_recovers_when_the_leading_call_is_cancelled() {
+        // arrange
+        let single_flight = Arc::new(SingleFlight::new());
+        let leader = {
+            let single_flight = single_flight.clone();
+            tokio::spawn(async move {
+                single_flight
+                    .run("charizard", || async {
+                        tokio::time::sleep(Duration::from_secs(60)).await;
+                        0
+                    })
+                    .await
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let waiter = {
+            let single_flight = single_flight.clone();
+            tokio::spawn(async move { single_flight.run("charizard", || async { 6 }).await })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        // act
+        leader.abort();
+
+        // assert
+        assert_eq!(waiter.await.unwrap(), 6);
+    }
+}
@@ -0,0 +1,47 @@
+use http_client::isahc::IsahcClient;
+use isahc::config::Configurable;
+use std::time::Duration;
+use surf::Client;
+use thiserror::Error;
+
+/// The http client couldn't be built with the given timeouts.
+#[derive(Error, Debug)]
+#[error("Failed to build the http client: {0}")]
+pub struct BuildClientError(#[from] isahc::Error);
+
+/// How long to wait for an upstream before giving up.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Timeouts {
+    /// Time allowed to establish a connection.
+    pub connect: Duration,
+    /// Time allowed for the whole request, including reading the response body and any retries.
+    pub total: Duration,
+}
+
+impl Timeouts {
+    /// Builds a surf client that gives up connecting after the connect timeout.
+    /// The total timeout is enforced by the caller around the whole request.
+    pub fn build_client(&self) -> Result<Client, BuildClientError> {
+        let client = isahc::HttpClient::builder()
+            .connect_timeout(self.connect)
+            .build()?;
+        Ok(Client::with_http_client(IsahcClient::from_client(client)))
+    }
+
+    /// Whether a transport error was caused by the connect timeout.
+    pub fn is_timeout(error: &surf::Error) -> bool {
+        matches!(
+            error.downcast_ref::<isahc::Error>(),
+            Some(isahc::Error::Timeout)
+        )
+    }
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            connect: Duration::from_secs(5),
+            total: Duration::from_secs(10),
+        }
+    }
+}
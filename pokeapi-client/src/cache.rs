@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A bounded in-memory cache whose entries expire after a fixed time to live.
+///
+/// When the cache is full, expired entries are dropped first and then the
+/// oldest entry is evicted to make room.
+pub struct TtlCache<K, V> {
+    ttl: Duration,
+    max_entries: usize,
+    entries: Mutex<HashMap<K, (Instant, V)>>,
+}
+
+impl<K, V> TtlCache<K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    pub fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            ttl,
+            max_entries,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some((inserted_at, value)) if inserted_at.elapsed() < self.ttl => Some(value.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn insert(&self, key: K, value: V) {
+        if self.max_entries == 0 {
+            return;
+        }
+
+        let mut entries = self.entries.lock().unwrap();
+        if !entries.contains_key(&key) && entries.len() >= self.max_entries {
+            let ttl = self.ttl;
+            entries.retain(|_, (inserted_at, _)| inserted_at.elapsed() < ttl);
+
+            if entries.len() >= self.max_entries {
+                let oldest = entries
+                    .iter()
+                    .min_by_key(|(_, (inserted_at, _))| *inserted_at)
+                    .map(|(key, _)| key.clone());
+                if let Some(oldest) = oldest {
+                    entries.remove(&oldest);
+                }
+            }
+        }
+        entries.insert(key, (Instant::now(), value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_returns_cached_values() {
+        // arrange
+        let cache = TtlCache::new(Duration::from_secs(60), 10);
+
+        // act
+        cache.insert("charizard", 6);
+
+        // assert
+        assert_eq!(cache.get(&"charizard"), Some(6));
+        assert_eq!(cache.get(&"zubat"), None);
+    }
+
+    #[test]
+    fn it_expires_entries_after_the_ttl() {
+        // arrange
+        let cache = TtlCache::new(Duration::from_millis(10), 10);
+        cache.insert("charizard", 6);
+
+        // act
+        std::thread::sleep(Duration::from_millis(20));
+
+        // assert
+        assert_eq!(cache.get(&"charizard"), None);
+    }
+
+    #[test]
+    fn it_evicts_the_oldest_entry_when_full() {
+        // arrange
+        let cache = TtlCache::new(Duration::from_secs(60), 2);
+        cache.insert("charizard", 6);
+        std::thread::sleep(Duration::from_millis(1));
+        cache.insert("zubat", 41);
+        std::thread::sleep(Duration::from_millis(1));
+
+        // act
+        cache.insert("mewtwo", 150);
+
+        // assert
+        assert_eq!(cache.get(&"charizard"), None);
+        assert_eq!(cache.get(&"zubat"), Some(41));
+        assert_eq!(cache.get(&"mewtwo"), Some(150));
+    }
+
+    #[test]
+    fn it_does_not_store_anything_with_no_capacity() {
+        // arrange
+        let cache = TtlCache::new(Duration::from_secs(60), 0);
+
+        // act
+        cache.insert("charizard", 6);
+
+        // assert
+        assert_eq!(cache.get(&"charizard"), None);
+    }
+}
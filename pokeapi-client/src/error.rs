@@ -0,0 +1,62 @@
+pub use http_support::ErrorContext;
+use std::mem::discriminant;
+use thiserror::Error;
+
+/// Why looking up a pokemon failed.
+///
+/// Errors compare equal when they are the same variant, whatever their context.
+#[derive(Error, Debug, Clone)]
+pub enum PokemonError {
+    #[error("Failed to find pokemon")]
+    NotFound,
+    #[error("Failed to deserialize pokemon data{0}")]
+    Deserialization(ErrorContext),
+    #[error("Failed to get pokemon{0}")]
+    Api(ErrorContext),
+    #[error("Failed to get pokemon, timed out")]
+    Timeout,
+}
+
+impl PartialEq for PokemonError {
+    fn eq(&self, other: &Self) -> bool {
+        discriminant(self) == discriminant(other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_describes_the_context() {
+        // arrange
+        let error = PokemonError::Deserialization(
+            ErrorContext::default()
+                .with_status(200)
+                .with_source("missing field `id`")
+                .with_body(" {\"name\": \"charizard\"}\n"),
+        );
+
+        // act
+        let message = error.to_string();
+
+        // assert
+        assert_eq!(
+            message,
+            "Failed to deserialize pokemon data: status 200, missing field `id`, body \"{\\\"name\\\": \\\"charizard\\\"}\""
+        );
+    }
+
+    #[test]
+    fn it_compares_errors_by_kind() {
+        // arrange
+        let with_context = PokemonError::Api(ErrorContext::default().with_status(500));
+
+        // act
+        let without_context = PokemonError::Api(ErrorContext::default());
+
+        // assert
+        assert_eq!(with_context, without_context);
+        assert_ne!(with_context, PokemonError::Timeout);
+    }
+}
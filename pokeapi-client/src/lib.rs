@@ -0,0 +1,31 @@
+//! A client for the [pokeapi](https://pokeapi.co/) pokemon species endpoint.
+//!
+//! Lookups are retried with exponential backoff, can be cached in memory, and concurrent lookups
+//! of the same pokemon share a single request.
+//!
+//! ```no_run
+//! use pokeapi_client::PokemonClient;
+//! use std::time::Duration;
+//!
+//! # async fn example() -> Result<(), pokeapi_client::PokemonError> {
+//! let client = PokemonClient::new("https://pokeapi.co".into())
+//!     .with_cache(Duration::from_secs(60 * 60), 1000);
+//! let charizard = client.get_pokemon("charizard").await?;
+//! println!("{:?}", charizard.get_description());
+//! # Ok(())
+//! # }
+//! ```
+
+mod cache;
+mod error;
+mod observer;
+mod pokemon_client;
+mod retry;
+
+pub use error::{ErrorContext, PokemonError};
+pub use http_support::{BuildClientError, Timeouts};
+pub use observer::PokemonObserver;
+pub use pokemon_client::{
+    Color, FlavorTextEntry, Generation, Habitat, Language, Pokemon, PokemonClient,
+};
+pub use retry::RetryPolicy;
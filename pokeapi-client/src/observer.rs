@@ -0,0 +1,15 @@
+use crate::error::PokemonError;
+use std::time::Duration;
+
+/// Notified about requests to pokeapi and cache lookups, for example to record metrics.
+pub trait PokemonObserver: Send + Sync {
+    /// Called after every request to pokeapi, including retries, with how long it took.
+    fn on_request(&self, _duration: Duration, _error: Option<&PokemonError>) {}
+
+    /// Called for every lookup in the species cache.
+    fn on_cache_lookup(&self, _hit: bool) {}
+}
+
+pub(crate) struct NoopObserver;
+
+impl PokemonObserver for NoopObserver {}
@@ -0,0 +1,887 @@
+use crate::cache::TtlCache;
+use crate::error::{ErrorContext, PokemonError};
+use crate::observer::{NoopObserver, PokemonObserver};
+use crate::retry::RetryPolicy;
+use http_support::{BuildClientError, SingleFlight, Timeouts};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use surf::{Client, StatusCode};
+use tracing::{field, info, info_span, warn, Instrument, Span};
+
+/// A pokemon species, as returned by `/api/v2/pokemon-species/{name}`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Pokemon {
+    pub id: i64,
+    pub name: String,
+    pub is_legendary: bool,
+    #[serde(default)]
+    pub is_mythical: bool,
+    pub habitat: Habitat,
+    #[serde(default)]
+    pub generation: Option<Generation>,
+    #[serde(default)]
+    pub color: Option<Color>,
+    pub flavor_text_entries: Vec<FlavorTextEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Habitat {
+    pub name: String,
+    pub url: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Generation {
+    pub name: String,
+    pub url: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Color {
+    pub name: String,
+    pub url: String,
+}
+
+impl Pokemon {
+    /// The first english flavor text, with pokeapi's line breaks replaced by spaces.
+    pub fn get_description(&self) -> Option<String> {
+        let description = &self
+            .flavor_text_entries
+            .iter()
+            .find(|entry| entry.language.name == "en");
+        description.map(|entry| entry.flavor_text.replace("\n", " ").replace("\u{c}", ""))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct FlavorTextEntry {
+    pub flavor_text: String,
+    pub language: Language,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+pub struct Language {
+    pub name: String,
+    pub url: String,
+}
+
+/// Looks up pokemon species from pokeapi. Clones share the cache and in flight requests.
+#[derive(Clone)]
+pub struct PokemonClient {
+    base_url: String,
+    client: Client, // Surfs clone implementation shares the underlying HttpClient
+    cache: Option<Arc<TtlCache<String, Pokemon>>>,
+    in_flight: Arc<SingleFlight<String, std::result::Result<Pokemon, PokemonError>>>,
+    retry_policy: RetryPolicy,
+    timeouts: Timeouts,
+    observer: Arc<dyn PokemonObserver>,
+}
+
+/// Looked up to check pokeapi is healthy.
+const HEALTH_CHECK_SPECIES: &str = "ditto";
+
+/// A failed species request, and whether it is worth retrying.
+enum SpeciesError {
+    Retryable(PokemonError),
+    Fatal(PokemonError),
+}
+
+impl PokemonClient {
+    /// A client for the pokeapi at `base_url`, such as `https://pokeapi.co`.
+    pub fn new(base_url: String) -> Self {
+        let timeouts = Timeouts::default();
+        Self {
+            base_url,
+            // Client::new panics when the http client can't be built too
+            client: timeouts
+                .build_client()
+                .expect("Failed to build the http client"),
+            cache: None,
+            in_flight: Arc::new(SingleFlight::new()),
+            retry_policy: RetryPolicy::none(),
+            timeouts,
+            observer: Arc::new(NoopObserver),
+        }
+    }
+
+    /// Gives up on species lookups once a timeout is hit. The total timeout covers every
+    /// attempt, including the delays between retries.
+    pub fn with_timeouts(mut self, timeouts: Timeouts) -> Result<Self, BuildClientError> {
+        self.client = timeouts.build_client()?;
+        self.timeouts = timeouts;
+        Ok(self)
+    }
+
+    /// Retries transport failures and retryable statuses when looking up a species.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Caches species lookups in memory, so repeated requests for the same pokemon skip the upstream.
+    pub fn with_cache(mut self, ttl: Duration, max_entries: usize) -> Self {
+        self.cache = Some(Arc::new(TtlCache::new(ttl, max_entries)));
+        self
+    }
+
+    /// Reports requests and cache lookups to the given observer.
+    pub fn with_observer(mut self, observer: Arc<dyn PokemonObserver>) -> Self {
+        self.observer = observer;
+        self
+    }
+
+    /// Checks pokeapi is healthy by looking up a species, bypassing the cache and retries.
+    /// The observer isn't told about it.
+    pub async fn check_health(&self) -> std::result::Result<(), PokemonError> {
+        let url = format!(
+            "{}/api/v2/pokemon-species/{}",
+            self.base_url, HEALTH_CHECK_SPECIES
+        );
+        match tokio::time::timeout(self.timeouts.total, self.request_pokemon_species(&url, &[]))
+            .await
+        {
+            Ok(Ok(_)) => Ok(()),
+            Ok(Err(SpeciesError::Retryable(err))) | Ok(Err(SpeciesError::Fatal(err))) => Err(err),
+            Err(_) => Err(PokemonError::Timeout),
+        }
+    }
+
+    /// Looks up the species with the given name.
+    pub async fn get_pokemon(&self, pokemon: &str) -> std::result::Result<Pokemon, PokemonError> {
+        self.get_pokemon_with_headers(pokemon, &[]).await
+    }
+
+    /// Looks up a species like `get_pokemon`, sending `headers` on every request to pokeapi, such
+    /// as a request id or trace context. Lookups coalesced with one already in flight only send
+    /// the headers of the first.
+    #[tracing::instrument(name = "get_pokemon", skip(self, headers))]
+    pub async fn get_pokemon_with_headers(
+        &self,
+        pokemon: &str,
+        headers: &[(&str, String)],
+    ) -> std::result::Result<Pokemon, PokemonError> {
+        if let Some(cache) = &self.cache {
+            let cached = cache.get(&pokemon.to_string());
+            self.observer.on_cache_lookup(cached.is_some());
+            if let Some(cached) = cached {
+                return Ok(cached);
+            }
+        }
+
+        // Concurrent lookups for the same pokemon share a single upstream request
+        self.in_flight
+            .run(pokemon.to_string(), || async {
+                let data = self.get_pokemon_species(pokemon, headers).await?;
+                if let Some(cache) = &self.cache {
+                    cache.insert(pokemon.to_string(), data.clone());
+                }
+                Ok(data)
+            })
+            .await
+    }
+
+    async fn get_pokemon_species(
+        &self,
+        pokemon: &str,
+        headers: &[(&str, String)],
+    ) -> std::result::Result<Pokemon, PokemonError> {
+        let url = format!("{}/api/v2/pokemon-species/{}", self.base_url, pokemon);
+
+        let deadline = tokio::time::Instant::now() + self.timeouts.total;
+        let mut attempt = 1;
+        loop {
+            let span = info_span!(
+                "pokeapi_request",
+                attempt,
+                status = field::Empty,
+                duration_ms = field::Empty
+            );
+            let start = Instant::now();
+            let response =
+                tokio::time::timeout_at(deadline, self.request_pokemon_species(&url, headers))
+                    .instrument(span.clone())
+                    .await
+                    .unwrap_or_else(|_| {
+                        span.in_scope(|| warn!("Pokemon species request timed out"));
+                        Err(SpeciesError::Retryable(PokemonError::Timeout))
+                    });
+            let duration = start.elapsed();
+            span.record("duration_ms", duration.as_millis() as u64);
+            self.observer.on_request(
+                duration,
+                match &response {
+                    Ok(_) => None,
+                    Err(SpeciesError::Retryable(err)) | Err(SpeciesError::Fatal(err)) => Some(err),
+                },
+            );
+
+            match response {
+                Ok(data) => return Ok(data),
+                Err(SpeciesError::Retryable(err)) if attempt < self.retry_policy.max_attempts => {
+                    let delay = self.retry_policy.delay_after(attempt);
+                    // Don't wait for a retry that the total timeout would cut short anyway
+                    if tokio::time::Instant::now() + delay >= deadline {
+                        return Err(err);
+                    }
+                    info!(
+                        delay_ms = delay.as_millis() as u64,
+                        next_attempt = attempt + 1,
+                        max_attempts = self.retry_policy.max_attempts,
+                        "Retrying pokemon species request"
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(SpeciesError::Retryable(err)) | Err(SpeciesError::Fatal(err)) => {
+                    return Err(err)
+                }
+            }
+        }
+    }
+
+    async fn request_pokemon_species(
+        &self,
+        url: &str,
+        headers: &[(&str, String)],
+    ) -> std::result::Result<Pokemon, SpeciesError> {
+        let mut request = self.client.get(url);
+        for (name, value) in headers {
+            request = request.header(*name, value.as_str());
+        }
+        let mut res = request.await.map_err(|err| {
+            warn!(error = %err, "Failed to send pokemon species request");
+            if Timeouts::is_timeout(&err) {
+                SpeciesError::Retryable(PokemonError::Timeout)
+            } else {
+                SpeciesError::Retryable(PokemonError::Api(
+                    ErrorContext::default().with_source(err.into_inner()),
+                ))
+            }
+        })?;
+
+        let status = res.status();
+        Span::current().record("status", u16::from(status));
+        if status == StatusCode::NotFound {
+            return Err(SpeciesError::Fatal(PokemonError::NotFound));
+        }
+
+        let context = ErrorContext::default().with_status(status.into());
+        let body = res.body_string().await.map_err(|err| {
+            warn!(error = %err, "Failed to read pokemon species response");
+            SpeciesError::Retryable(PokemonError::Api(
+                context.clone().with_source(err.into_inner()),
+            ))
+        })?;
+        match status {
+            StatusCode::Ok => serde_json::from_str(&body).map_err(|err| {
+                warn!(error = %err, "Failed to deserialize pokemon species");
+                SpeciesError::Fatal(PokemonError::Deserialization(
+                    context.with_source(err).with_body(&body),
+                ))
+            }),
+            status if self.retry_policy.is_retryable_status(status) => {
+                warn!(%status, "Pokeapi responded with a retryable status");
+                Err(SpeciesError::Retryable(PokemonError::Api(
+                    context.with_body(&body),
+                )))
+            }
+            status => {
+                warn!(%status, "Pokeapi responded with an unexpected status");
+                Err(SpeciesError::Fatal(PokemonError::Api(
+                    context.with_body(&body),
+                )))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn it_error_on_404() {
+        // arrange
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/charizard"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&mock_server)
+            .await;
+
+        let pokemon_client = PokemonClient::new(mock_server.uri());
+
+        // act
+        let res = pokemon_client.get_pokemon("charizard").await;
+
+        // assert
+        if let Err(err) = res {
+            assert_eq!(err, PokemonError::NotFound);
+        } else {
+            unreachable!();
+        }
+    }
+
+    #[tokio::test]
+    async fn it_errors_on_invalid_data() {
+        // arrange
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/charizard"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&mock_server)
+            .await;
+
+        let pokemon_client = PokemonClient::new(mock_server.uri());
+
+        // act
+        let res = pokemon_client.get_pokemon("charizard").await;
+
+        // assert
+        if let Err(err) = res {
+            assert_eq!(err, PokemonError::Deserialization(ErrorContext::default()));
+        } else {
+            unreachable!();
+        }
+    }
+
+    #[tokio::test]
+    async fn it_correctly_deserializes_pokemon_species() {
+        // arrange
+        let mock_server = MockServer::start().await;
+
+        let generated_pokemon = Pokemon {
+            id: 6,
+            name: "charizard".into(),
+            is_legendary: false,
+            is_mythical: false,
+            generation: None,
+            color: None,
+            habitat: Habitat {
+                name: "urban".into(),
+                url: "https://pokeapi.co/api/v2/pokemon-habitat/8/".into(),
+            },
+            flavor_text_entries: vec![FlavorTextEntry {
+                flavor_text: "Spits fire that is hot enough to melt boulders.\nKnown to cause forest fires unintentionally.".into(),
+                language: Language {
+					name: "en".into(),
+					url: "https://pokeapi.co/api/v2/language/9/".into()
+				},
+            }],
+        };
+
+        let mock_response = ResponseTemplate::new(200).set_body_json(json!(generated_pokemon));
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/charizard"))
+            .respond_with(mock_response)
+            .mount(&mock_server)
+            .await;
+
+        let pokemon_client = PokemonClient::new(mock_server.uri());
+
+        // act
+        let res = pokemon_client.get_pokemon("charizard").await.unwrap();
+
+        // assert
+        assert_eq!(res, generated_pokemon);
+    }
+
+    #[tokio::test]
+    async fn it_only_requests_a_cached_pokemon_once() {
+        // arrange
+        let mock_server = MockServer::start().await;
+
+        let generated_pokemon = Pokemon {
+            id: 41,
+            name: "zubat".into(),
+            is_legendary: false,
+            is_mythical: false,
+            generation: None,
+            color: None,
+            habitat: Habitat {
+                name: "cave".into(),
+                url: "https://pokeapi.co/api/v2/pokemon-habitat/1/".into(),
+            },
+            flavor_text_entries: vec![],
+        };
+
+        let mock_response = ResponseTemplate::new(200).set_body_json(json!(generated_pokemon));
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/zubat"))
+            .respond_with(mock_response)
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let pokemon_client =
+            PokemonClient::new(mock_server.uri()).with_cache(Duration::from_secs(60), 10);
+
+        // act
+        for _ in 0..3 {
+            let res = pokemon_client.get_pokemon("zubat").await.unwrap();
+            assert_eq!(res, generated_pokemon);
+        }
+
+        // assert
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn it_does_not_cache_errors() {
+        // arrange
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/charizard"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let pokemon_client =
+            PokemonClient::new(mock_server.uri()).with_cache(Duration::from_secs(60), 10);
+
+        // act
+        let first = pokemon_client.get_pokemon("charizard").await;
+        let second = pokemon_client.get_pokemon("charizard").await;
+
+        // assert
+        assert_eq!(first, Err(PokemonError::NotFound));
+        assert_eq!(second, Err(PokemonError::NotFound));
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn it_coalesces_concurrent_requests_for_the_same_pokemon() {
+        // arrange
+        let mock_server = MockServer::start().await;
+
+        let generated_pokemon = Pokemon {
+            id: 41,
+            name: "zubat".into(),
+            is_legendary: false,
+            is_mythical: false,
+            generation: None,
+            color: None,
+            habitat: Habitat {
+                name: "cave".into(),
+                url: "https://pokeapi.co/api/v2/pokemon-habitat/1/".into(),
+            },
+            flavor_text_entries: vec![],
+        };
+
+        let mock_response = ResponseTemplate::new(200)
+            .set_body_json(json!(generated_pokemon))
+            .set_delay(Duration::from_millis(200));
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/zubat"))
+            .respond_with(mock_response)
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let pokemon_client = PokemonClient::new(mock_server.uri());
+
+        // act
+        let handles: Vec<_> = (0..10)
+            .map(|_| {
+                let pokemon_client = pokemon_client.clone();
+                tokio::spawn(async move { pokemon_client.get_pokemon("zubat").await })
+            })
+            .collect();
+
+        // assert
+        for handle in handles {
+            assert_eq!(handle.await.unwrap(), Ok(generated_pokemon.clone()));
+        }
+        mock_server.verify().await;
+    }
+
+    fn fast_retry_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(1),
+            jitter: Duration::from_millis(0),
+            retryable_statuses: vec![500, 503],
+        }
+    }
+
+    #[tokio::test]
+    async fn it_retries_retryable_statuses_until_it_succeeds() {
+        // arrange
+        let mock_server = MockServer::start().await;
+
+        let generated_pokemon = Pokemon {
+            id: 41,
+            name: "zubat".into(),
+            is_legendary: false,
+            is_mythical: false,
+            generation: None,
+            color: None,
+            habitat: Habitat {
+                name: "cave".into(),
+                url: "https://pokeapi.co/api/v2/pokemon-habitat/1/".into(),
+            },
+            flavor_text_entries: vec![],
+        };
+
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/zubat"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/zubat"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(generated_pokemon)))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let pokemon_client =
+            PokemonClient::new(mock_server.uri()).with_retry_policy(fast_retry_policy());
+
+        // act
+        let res = pokemon_client.get_pokemon("zubat").await;
+
+        // assert
+        assert_eq!(res, Ok(generated_pokemon));
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn it_gives_up_after_the_max_attempts() {
+        // arrange
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/zubat"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(3)
+            .mount(&mock_server)
+            .await;
+
+        let pokemon_client =
+            PokemonClient::new(mock_server.uri()).with_retry_policy(fast_retry_policy());
+
+        // act
+        let res = pokemon_client.get_pokemon("zubat").await;
+
+        // assert
+        assert_eq!(res, Err(PokemonError::Api(ErrorContext::default())));
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn it_does_not_retry_non_retryable_failures() {
+        // arrange
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/missingno"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/zubat"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/charizard"))
+            .respond_with(ResponseTemplate::new(502))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let pokemon_client =
+            PokemonClient::new(mock_server.uri()).with_retry_policy(fast_retry_policy());
+
+        // act
+        let not_found = pokemon_client.get_pokemon("missingno").await;
+        let invalid_data = pokemon_client.get_pokemon("zubat").await;
+        let unlisted_status = pokemon_client.get_pokemon("charizard").await;
+
+        // assert
+        assert_eq!(not_found, Err(PokemonError::NotFound));
+        assert_eq!(
+            invalid_data,
+            Err(PokemonError::Deserialization(ErrorContext::default()))
+        );
+        assert_eq!(
+            unlisted_status,
+            Err(PokemonError::Api(ErrorContext::default()))
+        );
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn it_retries_connection_failures() {
+        // arrange
+        // Nothing is listening on the discard port, so every connection is refused
+        let observer = Arc::new(RecordingObserver::default());
+        let pokemon_client = PokemonClient::new("http://127.0.0.1:9".into())
+            .with_retry_policy(fast_retry_policy())
+            .with_observer(observer.clone());
+
+        // act
+        let res = pokemon_client.get_pokemon("zubat").await;
+
+        // assert
+        assert_eq!(res, Err(PokemonError::Api(ErrorContext::default())));
+        assert_eq!(observer.requests.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn it_times_out_on_a_slow_response() {
+        // arrange
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/zubat"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(5)))
+            .mount(&mock_server)
+            .await;
+
+        let pokemon_client = PokemonClient::new(mock_server.uri())
+            .with_timeouts(Timeouts {
+                connect: Duration::from_secs(1),
+                total: Duration::from_millis(100),
+            })
+            .unwrap();
+
+        // act
+        let res = pokemon_client.get_pokemon("zubat").await;
+
+        // assert
+        assert_eq!(res, Err(PokemonError::Timeout));
+    }
+
+    #[tokio::test]
+    async fn it_applies_the_total_timeout_across_retries() {
+        // arrange
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/zubat"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(5)))
+            .mount(&mock_server)
+            .await;
+
+        let observer = Arc::new(RecordingObserver::default());
+        let pokemon_client = PokemonClient::new(mock_server.uri())
+            .with_retry_policy(fast_retry_policy())
+            .with_timeouts(Timeouts {
+                connect: Duration::from_secs(1),
+                total: Duration::from_millis(200),
+            })
+            .unwrap()
+            .with_observer(observer.clone());
+
+        // act
+        let start = Instant::now();
+        let res = pokemon_client.get_pokemon("zubat").await;
+
+        // assert
+        assert_eq!(res, Err(PokemonError::Timeout));
+        assert!(start.elapsed() < Duration::from_millis(500));
+        assert_eq!(
+            *observer.requests.lock().unwrap(),
+            vec![Some(PokemonError::Timeout)]
+        );
+    }
+
+    #[derive(Default)]
+    struct RecordingObserver {
+        requests: std::sync::Mutex<Vec<Option<PokemonError>>>,
+        cache_lookups: std::sync::Mutex<Vec<bool>>,
+    }
+
+    impl PokemonObserver for RecordingObserver {
+        fn on_request(&self, _duration: Duration, error: Option<&PokemonError>) {
+            self.requests.lock().unwrap().push(error.cloned());
+        }
+
+        fn on_cache_lookup(&self, hit: bool) {
+            self.cache_lookups.lock().unwrap().push(hit);
+        }
+    }
+
+    #[tokio::test]
+    async fn it_reports_every_attempt_and_cache_lookup_to_the_observer() {
+        // arrange
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/zubat"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&mock_server)
+            .await;
+
+        let observer = Arc::new(RecordingObserver::default());
+        let pokemon_client = PokemonClient::new(mock_server.uri())
+            .with_cache(Duration::from_secs(60), 10)
+            .with_retry_policy(fast_retry_policy())
+            .with_observer(observer.clone());
+
+        // act
+        let res = pokemon_client.get_pokemon("zubat").await;
+
+        // assert
+        assert_eq!(res, Err(PokemonError::Api(ErrorContext::default())));
+        assert_eq!(
+            *observer.requests.lock().unwrap(),
+            vec![Some(PokemonError::Api(ErrorContext::default())); 3]
+        );
+        assert_eq!(*observer.cache_lookups.lock().unwrap(), vec![false]);
+    }
+
+    #[tokio::test]
+    async fn it_keeps_the_cause_of_deserialization_errors() {
+        // arrange
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/charizard"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("{\"id\": 6}"))
+            .mount(&mock_server)
+            .await;
+
+        let pokemon_client = PokemonClient::new(mock_server.uri());
+
+        // act
+        let res = pokemon_client.get_pokemon("charizard").await;
+
+        // assert
+        if let Err(PokemonError::Deserialization(context)) = res {
+            assert_eq!(context.status(), Some(200));
+            assert_eq!(context.body(), Some("{\"id\": 6}"));
+            assert!(context
+                .source()
+                .unwrap()
+                .to_string()
+                .starts_with("missing field `name`"));
+        } else {
+            unreachable!();
+        }
+    }
+
+    #[tokio::test]
+    async fn it_forwards_the_given_headers_on_every_attempt() {
+        // arrange
+        let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let mock_server = MockServer::start().await;
+
+        let generated_pokemon = Pokemon {
+            id: 41,
+            name: "zubat".into(),
+            is_legendary: false,
+            is_mythical: false,
+            generation: None,
+            color: None,
+            habitat: Habitat {
+                name: "cave".into(),
+                url: "https://pokeapi.co/api/v2/pokemon-habitat/1/".into(),
+            },
+            flavor_text_entries: vec![],
+        };
+
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/zubat"))
+            .and(header("x-request-id", "abc-123"))
+            .and(header("traceparent", traceparent))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/zubat"))
+            .and(header("x-request-id", "abc-123"))
+            .and(header("traceparent", traceparent))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(generated_pokemon)))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let pokemon_client =
+            PokemonClient::new(mock_server.uri()).with_retry_policy(fast_retry_policy());
+
+        // act
+        let res = pokemon_client
+            .get_pokemon_with_headers(
+                "zubat",
+                &[
+                    ("x-request-id", "abc-123".into()),
+                    ("traceparent", traceparent.into()),
+                ],
+            )
+            .await;
+
+        // assert
+        assert_eq!(res, Ok(generated_pokemon));
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn it_checks_health_without_the_cache() {
+        // arrange
+        let mock_server = MockServer::start().await;
+
+        let ditto = Pokemon {
+            id: 132,
+            name: "ditto".into(),
+            is_legendary: false,
+            is_mythical: false,
+            generation: None,
+            color: None,
+            habitat: Habitat {
+                name: "urban".into(),
+                url: "https://pokeapi.co/api/v2/pokemon-habitat/8/".into(),
+            },
+            flavor_text_entries: vec![],
+        };
+
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/ditto"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(ditto)))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let pokemon_client =
+            PokemonClient::new(mock_server.uri()).with_cache(Duration::from_secs(60), 10);
+
+        // act
+        let first = pokemon_client.check_health().await;
+        let second = pokemon_client.check_health().await;
+
+        // assert
+        assert_eq!(first, Ok(()));
+        assert_eq!(second, Ok(()));
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn it_is_unhealthy_when_pokeapi_fails() {
+        // arrange
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/ditto"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let pokemon_client =
+            PokemonClient::new(mock_server.uri()).with_retry_policy(fast_retry_policy());
+
+        // act
+        let health = pokemon_client.check_health().await;
+
+        // assert
+        assert_eq!(health, Err(PokemonError::Api(ErrorContext::default())));
+        mock_server.verify().await;
+    }
+}
@@ -0,0 +1,115 @@
+use rand::Rng;
+use std::time::Duration;
+use surf::StatusCode;
+
+/// Controls how many times, and how often, a failed upstream request is retried.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one.
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled for every retry after it.
+    pub base_delay: Duration,
+    /// Upper bound on the doubled delay, before jitter is added.
+    pub max_delay: Duration,
+    /// Up to this much random delay is added to every retry.
+    pub jitter: Duration,
+    /// Response statuses that are worth retrying.
+    pub retryable_statuses: Vec<u16>,
+}
+
+impl RetryPolicy {
+    /// A policy that only ever makes a single attempt.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_retryable_status(&self, status: StatusCode) -> bool {
+        self.retryable_statuses.contains(&(status as u16))
+    }
+
+    /// How long to wait after the given (1 based) attempt failed.
+    pub fn delay_after(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .base_delay
+            .checked_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay);
+
+        let jitter_ms = self.jitter.as_millis() as u64;
+        if jitter_ms == 0 {
+            return backoff;
+        }
+        backoff + Duration::from_millis(rand::thread_rng().gen_range(0..=jitter_ms))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(2),
+            jitter: Duration::from_millis(100),
+            retryable_statuses: vec![500, 502, 503, 504],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_doubles_the_delay_for_each_attempt() {
+        // arrange
+        let policy = RetryPolicy {
+            jitter: Duration::from_millis(0),
+            ..RetryPolicy::default()
+        };
+
+        // act & assert
+        assert_eq!(policy.delay_after(1), Duration::from_millis(100));
+        assert_eq!(policy.delay_after(2), Duration::from_millis(200));
+        assert_eq!(policy.delay_after(3), Duration::from_millis(400));
+    }
+
+    #[test]
+    fn it_caps_the_delay() {
+        // arrange
+        let policy = RetryPolicy {
+            jitter: Duration::from_millis(0),
+            ..RetryPolicy::default()
+        };
+
+        // act & assert
+        assert_eq!(policy.delay_after(10), Duration::from_secs(2));
+        assert_eq!(policy.delay_after(u32::MAX), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn it_adds_bounded_jitter() {
+        // arrange
+        let policy = RetryPolicy::default();
+
+        // act & assert
+        for _ in 0..100 {
+            let delay = policy.delay_after(1);
+            assert!(delay >= Duration::from_millis(100));
+            assert!(delay <= Duration::from_millis(200));
+        }
+    }
+
+    #[test]
+    fn it_only_retries_configured_statuses() {
+        // arrange
+        let policy = RetryPolicy::default();
+
+        // act & assert
+        assert!(policy.is_retryable_status(StatusCode::ServiceUnavailable));
+        assert!(!policy.is_retryable_status(StatusCode::NotFound));
+        assert!(!policy.is_retryable_status(StatusCode::BadRequest));
+    }
+}
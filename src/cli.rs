@@ -0,0 +1,168 @@
+use clap::{Parser, Subcommand};
+use funtranslations_client::TranslationType;
+
+/// Shakespearean pokemon descriptions, as a web server or from the command line.
+///
+/// Settings are read from the config file and `SHAKESPEAREAN_` environment variables,
+/// and can be overridden with flags.
+#[derive(Parser, Debug)]
+#[command(version)]
+pub struct Cli {
+    /// A TOML config file, instead of the one named by SHAKESPEAREAN_CONFIG_FILE
+    #[arg(long, global = true)]
+    pub config: Option<String>,
+    /// Overrides pokemon_api_base_url
+    #[arg(long, global = true)]
+    pub pokemon_api_base_url: Option<String>,
+    /// Overrides translation_api_base_url
+    #[arg(long, global = true)]
+    pub translation_api_base_url: Option<String>,
+    /// Runs the server when no command is given
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+pub enum Command {
+    /// Runs the web server
+    Serve {
+        /// Overrides port
+        #[arg(long)]
+        port: Option<u16>,
+        /// Overrides log_format
+        #[arg(long, value_parser = ["pretty", "json"])]
+        log_format: Option<String>,
+    },
+    /// Looks up a pokemon on pokeapi and prints it as JSON, as served by /pokemon/<name>
+    Lookup {
+        /// The name of the pokemon, such as charizard
+        name: String,
+    },
+    /// Translates text with funtranslations and prints the translation
+    Translate {
+        /// The translation to use, one of those listed by /translations
+        #[arg(long, default_value = "shakespeare", value_parser = parse_style)]
+        style: TranslationType,
+        /// Overrides translation_local, for example `primary` to translate offline when possible
+        #[arg(long, value_parser = ["disabled", "fallback", "primary"])]
+        local: Option<String>,
+        /// The text to translate
+        text: String,
+    },
+}
+
+fn parse_style(style: &str) -> Result<TranslationType, String> {
+    TranslationType::from_name(style).ok_or_else(|| {
+        let styles: Vec<_> = TranslationType::ALL.iter().map(|t| t.name()).collect();
+        format!("expected one of: {}", styles.join(", "))
+    })
+}
+
+impl Cli {
+    /// The settings given as flags, which take priority over the config file and environment.
+    pub fn overrides(&self) -> Vec<(String, String)> {
+        let mut overrides = vec![
+            ("config_file", self.config.clone()),
+            ("pokemon_api_base_url", self.pokemon_api_base_url.clone()),
+            (
+                "translation_api_base_url",
+                self.translation_api_base_url.clone(),
+            ),
+        ];
+        match &self.command {
+            Some(Command::Serve { port, log_format }) => {
+                overrides.push(("port", port.map(|port| port.to_string())));
+                overrides.push(("log_format", log_format.clone()));
+            }
+            Some(Command::Translate { local, .. }) => {
+                overrides.push(("translation_local", local.clone()));
+            }
+            Some(Command::Lookup { .. }) | None => {}
+        }
+        overrides
+            .into_iter()
+            .filter_map(|(key, value)| value.map(|value| (key.to_string(), value)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Cli {
+        Cli::try_parse_from(std::iter::once("shakespearean_pokemon").chain(args.iter().copied()))
+            .unwrap()
+    }
+
+    #[test]
+    fn it_serves_without_a_command() {
+        // act
+        let cli = parse(&[]);
+
+        // assert
+        assert_eq!(cli.command, None);
+        assert!(cli.overrides().is_empty());
+    }
+
+    #[test]
+    fn it_overrides_the_config_with_flags() {
+        // act
+        let cli = parse(&[
+            "serve",
+            "--port",
+            "8080",
+            "--config",
+            "config.toml",
+            "--pokemon-api-base-url",
+            "http://localhost:9000",
+        ]);
+
+        // assert
+        let mut overrides = cli.overrides();
+        overrides.sort();
+        assert_eq!(
+            overrides,
+            vec![
+                ("config_file".to_string(), "config.toml".to_string()),
+                (
+                    "pokemon_api_base_url".to_string(),
+                    "http://localhost:9000".to_string()
+                ),
+                ("port".to_string(), "8080".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_parses_the_translation_style() {
+        // act
+        let cli = parse(&["translate", "--style", "yoda", "You are brave"]);
+
+        // assert
+        assert_eq!(
+            cli.command,
+            Some(Command::Translate {
+                style: TranslationType::YODA,
+                local: None,
+                text: "You are brave".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn it_rejects_unknown_translation_styles() {
+        // act
+        let result = Cli::try_parse_from([
+            "shakespearean_pokemon",
+            "translate",
+            "--style",
+            "not-a-style",
+            "hi",
+        ]);
+
+        // assert
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("expected one of: yoda, shakespeare"));
+    }
+}
@@ -1,16 +1,85 @@
+use funtranslations_client::TranslationError;
+use pokeapi_client::PokemonError;
+use std::mem::discriminant;
 use thiserror::Error;
-#[derive(Error, Debug, PartialEq)]
+
+/// Why an upstream client failed. Failed requests carry the upstream status, the start of the
+/// body and the underlying error, whichever are known.
+///
+/// Errors compare equal when they are the same kind, whatever their context.
+#[allow(clippy::enum_variant_names)]
+#[derive(Error, Debug, Clone)]
 pub enum ClientError {
     #[error("Failed to find pokemon")]
     PokemonNotFoundError,
-    #[error("Failed to deserialize pokemon data")]
-    PokemonDeserializationError,
-    #[error("Failed to get pokemon")]
-    PokemonAPIError,
-    #[error("Failed to deserialize shakespeare data")]
-    TranslationDeserializationError,
-    #[error("Failed to get shakespeare translation")]
-    TranslationAPIError,
-    #[error("Failed to get shakespeare translation, too many requests")]
+    #[error("Failed to deserialize pokemon data{0}")]
+    PokemonDeserializationError(pokeapi_client::ErrorContext),
+    #[error("Failed to get pokemon{0}")]
+    PokemonAPIError(pokeapi_client::ErrorContext),
+    #[error("Failed to get pokemon, timed out")]
+    PokemonAPITimeout,
+    #[error("Failed to deserialize translation data{0}")]
+    TranslationDeserializationError(funtranslations_client::ErrorContext),
+    #[error("Failed to get translation{0}")]
+    TranslationAPIError(funtranslations_client::ErrorContext),
+    #[error("Failed to get translation, too many requests")]
     TranslationTooManyRequestsError,
+    #[error("Failed to get translation, circuit breaker is open")]
+    TranslationCircuitOpenError,
+    #[error("Failed to get translation, timed out")]
+    TranslationTimeout,
+    #[error("Failed to get translation, not supported offline")]
+    TranslationUnsupportedError,
+}
+
+impl ClientError {
+    /// The variant name, for labelling metrics.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ClientError::PokemonNotFoundError => "PokemonNotFoundError",
+            ClientError::PokemonDeserializationError(_) => "PokemonDeserializationError",
+            ClientError::PokemonAPIError(_) => "PokemonAPIError",
+            ClientError::PokemonAPITimeout => "PokemonAPITimeout",
+            ClientError::TranslationDeserializationError(_) => "TranslationDeserializationError",
+            ClientError::TranslationAPIError(_) => "TranslationAPIError",
+            ClientError::TranslationTooManyRequestsError => "TranslationTooManyRequestsError",
+            ClientError::TranslationCircuitOpenError => "TranslationCircuitOpenError",
+            ClientError::TranslationTimeout => "TranslationTimeout",
+            ClientError::TranslationUnsupportedError => "TranslationUnsupportedError",
+        }
+    }
+}
+
+impl PartialEq for ClientError {
+    fn eq(&self, other: &Self) -> bool {
+        discriminant(self) == discriminant(other)
+    }
+}
+
+impl From<PokemonError> for ClientError {
+    fn from(error: PokemonError) -> Self {
+        match error {
+            PokemonError::NotFound => ClientError::PokemonNotFoundError,
+            PokemonError::Deserialization(context) => {
+                ClientError::PokemonDeserializationError(context)
+            }
+            PokemonError::Api(context) => ClientError::PokemonAPIError(context),
+            PokemonError::Timeout => ClientError::PokemonAPITimeout,
+        }
+    }
+}
+
+impl From<TranslationError> for ClientError {
+    fn from(error: TranslationError) -> Self {
+        match error {
+            TranslationError::Deserialization(context) => {
+                ClientError::TranslationDeserializationError(context)
+            }
+            TranslationError::Api(context) => ClientError::TranslationAPIError(context),
+            TranslationError::TooManyRequests => ClientError::TranslationTooManyRequestsError,
+            TranslationError::CircuitOpen => ClientError::TranslationCircuitOpenError,
+            TranslationError::Timeout => ClientError::TranslationTimeout,
+            TranslationError::Unsupported => ClientError::TranslationUnsupportedError,
+        }
+    }
 }
@@ -1,3 +1,3 @@
 pub mod client_error;
-pub mod pokemon_client;
-pub mod translation_client;
+pub mod pokemon_source;
+pub mod translator;
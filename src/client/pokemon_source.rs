@@ -0,0 +1,34 @@
+use super::client_error::ClientError;
+use crate::request_context::RequestContext;
+use async_trait::async_trait;
+use pokeapi_client::{Pokemon, PokemonClient};
+
+/// Somewhere pokemon species can be looked up by name, such as pokeapi.
+#[async_trait]
+pub trait PokemonSource: Send + Sync {
+    async fn get_species(
+        &self,
+        name: &str,
+        context: &RequestContext,
+    ) -> Result<Pokemon, ClientError>;
+
+    /// Whether species can currently be looked up.
+    async fn check_health(&self) -> Result<(), ClientError>;
+}
+
+#[async_trait]
+impl PokemonSource for PokemonClient {
+    async fn get_species(
+        &self,
+        name: &str,
+        context: &RequestContext,
+    ) -> Result<Pokemon, ClientError> {
+        Ok(self
+            .get_pokemon_with_headers(name, &context.headers())
+            .await?)
+    }
+
+    async fn check_health(&self) -> Result<(), ClientError> {
+        Ok(self.check_health().await?)
+    }
+}
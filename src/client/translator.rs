@@ -0,0 +1,65 @@
+use super::client_error::ClientError;
+use crate::request_context::RequestContext;
+use async_trait::async_trait;
+use funtranslations_client::{
+    CircuitState, LocalTranslator, Translation, TranslationClient, TranslationSource,
+    TranslationType,
+};
+
+/// Translates text into one of the funtranslations styles.
+#[async_trait]
+pub trait Translator: Send + Sync {
+    async fn translate(
+        &self,
+        text: &str,
+        translation_type: TranslationType,
+        context: &RequestContext,
+    ) -> Result<Translation, ClientError>;
+
+    /// Translators without a circuit breaker are always closed.
+    fn circuit_state(&self) -> CircuitState {
+        CircuitState::Closed
+    }
+
+    /// Translators that don't call an upstream are always healthy.
+    async fn check_health(&self) -> Result<(), ClientError> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Translator for TranslationClient {
+    async fn translate(
+        &self,
+        text: &str,
+        translation_type: TranslationType,
+        context: &RequestContext,
+    ) -> Result<Translation, ClientError> {
+        Ok(self
+            .translate(text, translation_type, &context.headers())
+            .await?)
+    }
+
+    fn circuit_state(&self) -> CircuitState {
+        self.circuit_state()
+    }
+
+    async fn check_health(&self) -> Result<(), ClientError> {
+        Ok(self.check_health().await?)
+    }
+}
+
+#[async_trait]
+impl Translator for LocalTranslator {
+    async fn translate(
+        &self,
+        text: &str,
+        translation_type: TranslationType,
+        _context: &RequestContext,
+    ) -> Result<Translation, ClientError> {
+        Ok(Translation {
+            text: self.get_translation(text, translation_type).await?,
+            source: TranslationSource::Offline,
+        })
+    }
+}
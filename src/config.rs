@@ -0,0 +1,629 @@
+use crate::secret::Secret;
+use crate::telemetry::LogFormat;
+use funtranslations_client::LocalTranslation;
+use pokeapi_client::RetryPolicy;
+use serde::Deserialize;
+use std::collections::HashMap;
+use thiserror::Error;
+use url::Url;
+
+/// Environment variables are only read when they start with this prefix, which is stripped
+/// along with the case to get the config key, for example `SHAKESPEAREAN_PORT` sets `port`.
+pub const ENV_PREFIX: &str = "SHAKESPEAREAN_";
+
+/// The key, after the prefix, of the environment variable naming the optional TOML config file.
+const CONFIG_FILE_KEY: &str = "config_file";
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("Failed to read config file {path}: {source}")]
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+    #[error("Failed to parse config file {path}: {source}")]
+    Parse {
+        path: String,
+        source: toml::de::Error,
+    },
+    #[error("Invalid config:\n{}", .0.join("\n"))]
+    Invalid(Vec<String>),
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default = "default_port")]
+    pub port: u16,
+    pub api_token: Option<Secret>,
+    /// A file containing the api token, for docker and kubernetes secrets
+    pub api_token_file: Option<String>,
+    #[serde(default = "default_pokemon_api_base_url")]
+    pub pokemon_api_base_url: String,
+    #[serde(default = "default_translation_api_base_url")]
+    pub translation_api_base_url: String,
+    #[serde(default = "default_pokemon_cache_ttl_secs")]
+    pub pokemon_cache_ttl_secs: u64,
+    #[serde(default = "default_pokemon_cache_max_entries")]
+    pub pokemon_cache_max_entries: usize,
+    pub translation_cache_path: Option<String>,
+    #[serde(default = "default_pokemon_retry_max_attempts")]
+    pub pokemon_retry_max_attempts: u32,
+    #[serde(default = "default_pokemon_retry_base_delay_ms")]
+    pub pokemon_retry_base_delay_ms: u64,
+    #[serde(default = "default_pokemon_retry_jitter_ms")]
+    pub pokemon_retry_jitter_ms: u64,
+    #[serde(default = "default_pokemon_retry_statuses")]
+    pub pokemon_retry_statuses: Vec<u16>,
+    #[serde(default = "default_translation_rate_limit_requests")]
+    pub translation_rate_limit_requests: u32,
+    #[serde(default = "default_translation_rate_limit_period_secs")]
+    pub translation_rate_limit_period_secs: u64,
+    #[serde(default = "default_translation_max_retry_after_secs")]
+    pub translation_max_retry_after_secs: u64,
+    #[serde(default = "default_translation_circuit_failure_threshold")]
+    pub translation_circuit_failure_threshold: u32,
+    #[serde(default = "default_translation_circuit_cool_down_secs")]
+    pub translation_circuit_cool_down_secs: u64,
+    #[serde(default = "default_connect_timeout_ms")]
+    pub pokemon_api_connect_timeout_ms: u64,
+    #[serde(default = "default_total_timeout_ms")]
+    pub pokemon_api_timeout_ms: u64,
+    #[serde(default = "default_connect_timeout_ms")]
+    pub translation_api_connect_timeout_ms: u64,
+    #[serde(default = "default_total_timeout_ms")]
+    pub translation_api_timeout_ms: u64,
+    pub translation_rules_path: Option<String>,
+    #[serde(default = "default_translation_local")]
+    pub translation_local: LocalTranslation,
+    #[serde(default = "default_log_format")]
+    pub log_format: LogFormat,
+    #[serde(default = "default_readiness_cache_ttl_secs")]
+    pub readiness_cache_ttl_secs: u64,
+    #[serde(default = "default_shutdown_readiness_delay_ms")]
+    pub shutdown_readiness_delay_ms: u64,
+    #[serde(default = "default_shutdown_drain_timeout_ms")]
+    pub shutdown_drain_timeout_ms: u64,
+}
+
+fn default_port() -> u16 {
+    5000
+}
+
+fn default_pokemon_api_base_url() -> String {
+    "https://pokeapi.co".into()
+}
+
+fn default_translation_api_base_url() -> String {
+    "https://api.funtranslations.com".into()
+}
+
+fn default_pokemon_cache_ttl_secs() -> u64 {
+    60 * 60 * 24
+}
+
+fn default_pokemon_cache_max_entries() -> usize {
+    1000
+}
+
+fn default_pokemon_retry_max_attempts() -> u32 {
+    RetryPolicy::default().max_attempts
+}
+
+fn default_pokemon_retry_base_delay_ms() -> u64 {
+    RetryPolicy::default().base_delay.as_millis() as u64
+}
+
+fn default_pokemon_retry_jitter_ms() -> u64 {
+    RetryPolicy::default().jitter.as_millis() as u64
+}
+
+fn default_pokemon_retry_statuses() -> Vec<u16> {
+    RetryPolicy::default().retryable_statuses
+}
+
+// The free funtranslations tier allows 5 requests an hour
+fn default_translation_rate_limit_requests() -> u32 {
+    5
+}
+
+fn default_translation_rate_limit_period_secs() -> u64 {
+    60 * 60
+}
+
+fn default_translation_max_retry_after_secs() -> u64 {
+    60
+}
+
+fn default_translation_circuit_failure_threshold() -> u32 {
+    5
+}
+
+fn default_translation_circuit_cool_down_secs() -> u64 {
+    30
+}
+
+fn default_connect_timeout_ms() -> u64 {
+    pokeapi_client::Timeouts::default().connect.as_millis() as u64
+}
+
+fn default_total_timeout_ms() -> u64 {
+    pokeapi_client::Timeouts::default().total.as_millis() as u64
+}
+
+fn default_translation_local() -> LocalTranslation {
+    LocalTranslation::Disabled
+}
+
+fn default_log_format() -> LogFormat {
+    LogFormat::Pretty
+}
+
+fn default_readiness_cache_ttl_secs() -> u64 {
+    5
+}
+
+fn default_shutdown_readiness_delay_ms() -> u64 {
+    1000
+}
+
+/// Docker sends SIGKILL 10 seconds after SIGTERM, so give up on draining before then,
+/// including the readiness delay
+fn default_shutdown_drain_timeout_ms() -> u64 {
+    8000
+}
+
+impl Config {
+    /// Loads the config from the process environment, with `overrides` taking priority over it.
+    pub fn load(overrides: Vec<(String, String)>) -> Result<Self, ConfigError> {
+        Self::from_vars(std::env::vars(), overrides)
+    }
+
+    /// Layers the config from, in increasing priority: the defaults, the TOML file named by
+    /// `SHAKESPEAREAN_CONFIG_FILE`, the `SHAKESPEAREAN_` environment variables and `overrides`,
+    /// such as command line flags, which are keyed by the setting name.
+    /// The result is validated, reporting every problem found.
+    fn from_vars<I>(vars: I, overrides: Vec<(String, String)>) -> Result<Self, ConfigError>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let mut env: HashMap<String, String> = vars
+            .into_iter()
+            .filter_map(|(key, value)| {
+                key.strip_prefix(ENV_PREFIX)
+                    .map(|key| (key.to_lowercase(), value))
+            })
+            .collect();
+        env.extend(overrides);
+
+        let mut values = match env.remove(CONFIG_FILE_KEY) {
+            Some(path) => read_file(&path)?,
+            None => HashMap::new(),
+        };
+        values.extend(env);
+
+        // Check each value on its own, so one that can't be read doesn't hide the other problems
+        let mut problems = Vec::new();
+        values.retain(|key, value| {
+            match envy::from_iter::<_, Config>(vec![(key.clone(), value.clone())]) {
+                Ok(_) => true,
+                Err(error) => {
+                    problems.push(error.to_string());
+                    false
+                }
+            }
+        });
+        problems.sort();
+
+        let mut config: Config = envy::from_iter(values)
+            .map_err(|error| ConfigError::Invalid(vec![error.to_string()]))?;
+        // The clients add paths to the base urls, so avoid doubling up the slash
+        for url in [
+            &mut config.pokemon_api_base_url,
+            &mut config.translation_api_base_url,
+        ] {
+            let trimmed = url.trim_end_matches('/').len();
+            url.truncate(trimmed);
+        }
+
+        problems.extend(config.problems());
+        if !problems.is_empty() {
+            return Err(ConfigError::Invalid(problems));
+        }
+        Ok(config)
+    }
+
+    fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.port == 0 {
+            problems.push("port must be between 1 and 65535".to_string());
+        }
+        for (key, url) in [
+            ("pokemon_api_base_url", &self.pokemon_api_base_url),
+            ("translation_api_base_url", &self.translation_api_base_url),
+        ] {
+            if let Err(problem) = validate_base_url(url) {
+                problems.push(format!("{} {:?} {}", key, url, problem));
+            }
+        }
+        if self.api_token.is_some() && self.api_token_file.is_some() {
+            problems.push("api_token and api_token_file can't both be set".to_string());
+        }
+        for (key, value) in [
+            (
+                "pokemon_retry_max_attempts",
+                self.pokemon_retry_max_attempts,
+            ),
+            (
+                "translation_rate_limit_requests",
+                self.translation_rate_limit_requests,
+            ),
+            (
+                "translation_circuit_failure_threshold",
+                self.translation_circuit_failure_threshold,
+            ),
+        ] {
+            if value == 0 {
+                problems.push(format!("{} must be at least 1", key));
+            }
+        }
+        for (key, timeout) in [
+            (
+                "pokemon_api_connect_timeout_ms",
+                self.pokemon_api_connect_timeout_ms,
+            ),
+            ("pokemon_api_timeout_ms", self.pokemon_api_timeout_ms),
+            (
+                "translation_api_connect_timeout_ms",
+                self.translation_api_connect_timeout_ms,
+            ),
+            (
+                "translation_api_timeout_ms",
+                self.translation_api_timeout_ms,
+            ),
+        ] {
+            if timeout == 0 {
+                problems.push(format!("{} must be greater than 0", key));
+            }
+        }
+        problems
+    }
+
+    /// The funtranslations api token, from either `api_token` or the file at `api_token_file`.
+    pub fn api_token(&self) -> Result<Option<Secret>, String> {
+        match (&self.api_token, &self.api_token_file) {
+            (Some(token), _) => Ok(Some(token.clone())),
+            (None, Some(path)) => Secret::from_file(path)
+                .map(Some)
+                .map_err(|error| format!("Failed to read api token file {}: {}", path, error)),
+            (None, None) => Ok(None),
+        }
+    }
+}
+
+fn validate_base_url(url: &str) -> Result<(), String> {
+    let parsed = Url::parse(url).map_err(|error| format!("is not a valid url: {}", error))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err("must be an http or https url".into());
+    }
+    if !parsed.has_host() {
+        return Err("must have a host".into());
+    }
+    if parsed.query().is_some() || parsed.fragment().is_some() {
+        return Err("must not have a query or fragment".into());
+    }
+    Ok(())
+}
+
+/// Reads a TOML config file as strings, in the same form as environment variables.
+/// Arrays become comma separated lists.
+fn read_file(path: &str) -> Result<HashMap<String, String>, ConfigError> {
+    let contents = std::fs::read_to_string(path).map_err(|source| ConfigError::Io {
+        path: path.to_string(),
+        source,
+    })?;
+    let table: toml::value::Table =
+        toml::from_str(&contents).map_err(|source| ConfigError::Parse {
+            path: path.to_string(),
+            source,
+        })?;
+    let mut values = HashMap::new();
+    let mut problems = Vec::new();
+    for (key, value) in table {
+        match to_env_value(&value) {
+            Some(value) => {
+                values.insert(key, value);
+            }
+            None => problems.push(format!(
+                "{} must be a string, number, boolean or array",
+                key
+            )),
+        }
+    }
+    if !problems.is_empty() {
+        problems.sort();
+        return Err(ConfigError::Invalid(problems));
+    }
+    Ok(values)
+}
+
+fn to_env_value(value: &toml::Value) -> Option<String> {
+    match value {
+        toml::Value::String(value) => Some(value.clone()),
+        toml::Value::Integer(value) => Some(value.to_string()),
+        toml::Value::Float(value) => Some(value.to_string()),
+        toml::Value::Boolean(value) => Some(value.to_string()),
+        toml::Value::Array(values) => values
+            .iter()
+            .map(|value| match value {
+                toml::Value::Array(_) | toml::Value::Table(_) => None,
+                value => to_env_value(value),
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(|values| values.join(",")),
+        toml::Value::Table(_) | toml::Value::Datetime(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn vars(vars: Vec<(&str, &str)>) -> Vec<(String, String)> {
+        vars.into_iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn config_file(contents: &str) -> tempfile::NamedTempFile {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(file, "{}", contents).unwrap();
+        file
+    }
+
+    fn problems(result: Result<Config, ConfigError>) -> Vec<String> {
+        match result {
+            Err(ConfigError::Invalid(problems)) => problems,
+            other => panic!("Expected invalid config, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn it_uses_the_defaults_without_any_config() {
+        // act
+        let config = Config::from_vars(vec![], vec![]).unwrap();
+
+        // assert
+        assert_eq!(config.port, 5000);
+        assert_eq!(config.pokemon_api_base_url, "https://pokeapi.co");
+        assert_eq!(
+            config.translation_api_base_url,
+            "https://api.funtranslations.com"
+        );
+        assert_eq!(config.pokemon_cache_max_entries, 1000);
+    }
+
+    #[test]
+    fn it_only_reads_prefixed_environment_variables() {
+        // arrange
+        let vars = vars(vec![
+            ("SHAKESPEAREAN_PORT", "8080"),
+            ("PORT", "9090"),
+            ("PATH", "/usr/bin"),
+        ]);
+
+        // act
+        let config = Config::from_vars(vars, vec![]).unwrap();
+
+        // assert
+        assert_eq!(config.port, 8080);
+    }
+
+    #[test]
+    fn it_layers_the_environment_over_the_config_file() {
+        // arrange
+        let file = config_file(
+            r#"
+            port = 8080
+            pokemon_cache_max_entries = 10
+            pokemon_retry_statuses = [500, 503]
+            "#,
+        );
+        let vars = vars(vec![
+            ("SHAKESPEAREAN_CONFIG_FILE", file.path().to_str().unwrap()),
+            ("SHAKESPEAREAN_PORT", "9090"),
+        ]);
+
+        // act
+        let config = Config::from_vars(vars, vec![]).unwrap();
+
+        // assert
+        assert_eq!(config.port, 9090);
+        assert_eq!(config.pokemon_cache_max_entries, 10);
+        assert_eq!(config.pokemon_retry_statuses, vec![500, 503]);
+    }
+
+    #[test]
+    fn it_layers_overrides_over_the_environment() {
+        // arrange
+        let file = config_file("port = 8080\n");
+        let vars = vars(vec![("SHAKESPEAREAN_PORT", "9090")]);
+        let overrides = vec![
+            (
+                "config_file".to_string(),
+                file.path().to_str().unwrap().to_string(),
+            ),
+            ("log_format".to_string(), "json".to_string()),
+        ];
+
+        // act
+        let config = Config::from_vars(vars, overrides).unwrap();
+
+        // assert
+        assert_eq!(config.port, 9090);
+        assert_eq!(config.log_format, LogFormat::Json);
+    }
+
+    #[test]
+    fn it_rejects_unknown_keys() {
+        // arrange
+        let vars = vars(vec![("SHAKESPEAREAN_PROT", "8080")]);
+
+        // act
+        let problems = problems(Config::from_vars(vars, vec![]));
+
+        // assert
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("prot"));
+    }
+
+    #[test]
+    fn it_rejects_values_of_the_wrong_type() {
+        // arrange
+        let vars = vars(vec![("SHAKESPEAREAN_PORT", "seventy")]);
+
+        // act
+        let problems = problems(Config::from_vars(vars, vec![]));
+
+        // assert
+        assert!(problems[0].contains("port"));
+    }
+
+    #[test]
+    fn it_reports_every_problem() {
+        // arrange
+        let vars = vars(vec![
+            ("SHAKESPEAREAN_PORT", "0"),
+            ("SHAKESPEAREAN_POKEMON_API_BASE_URL", "pokeapi.co"),
+            (
+                "SHAKESPEAREAN_TRANSLATION_API_BASE_URL",
+                "ftp://example.com",
+            ),
+        ]);
+
+        // act
+        let error = Config::from_vars(vars, vec![]).unwrap_err();
+
+        // assert
+        let message = error.to_string();
+        assert!(message.contains("port must be between 1 and 65535"));
+        assert!(message.contains("pokemon_api_base_url \"pokeapi.co\" is not a valid url"));
+        assert!(message.contains(
+            "translation_api_base_url \"ftp://example.com\" must be an http or https url"
+        ));
+    }
+
+    #[test]
+    fn it_reports_values_that_cannot_be_read_with_every_other_problem() {
+        // arrange
+        let vars = vars(vec![
+            ("SHAKESPEAREAN_PROT", "8080"),
+            ("SHAKESPEAREAN_POKEMON_CACHE_TTL_SECS", "a day"),
+            ("SHAKESPEAREAN_POKEMON_API_BASE_URL", "pokeapi.co"),
+            ("SHAKESPEAREAN_TRANSLATION_RATE_LIMIT_REQUESTS", "0"),
+            ("SHAKESPEAREAN_TRANSLATION_CIRCUIT_FAILURE_THRESHOLD", "0"),
+        ]);
+
+        // act
+        let problems = problems(Config::from_vars(vars, vec![]));
+
+        // assert
+        assert_eq!(problems.len(), 5, "{:?}", problems);
+        assert!(problems.iter().any(|problem| problem.contains("prot")));
+        assert!(problems
+            .iter()
+            .any(|problem| problem.contains("pokemon_cache_ttl_secs")));
+        assert!(problems.contains(
+            &"pokemon_api_base_url \"pokeapi.co\" is not a valid url: relative URL without a base"
+                .to_string()
+        ));
+        assert!(
+            problems.contains(&"translation_rate_limit_requests must be at least 1".to_string())
+        );
+        assert!(problems
+            .contains(&"translation_circuit_failure_threshold must be at least 1".to_string()));
+    }
+
+    #[test]
+    fn it_trims_trailing_slashes_from_the_base_urls() {
+        // arrange
+        let vars = vars(vec![(
+            "SHAKESPEAREAN_POKEMON_API_BASE_URL",
+            "http://localhost:8080/",
+        )]);
+
+        // act
+        let config = Config::from_vars(vars, vec![]).unwrap();
+
+        // assert
+        assert_eq!(config.pokemon_api_base_url, "http://localhost:8080");
+    }
+
+    #[test]
+    fn it_rejects_nested_tables_in_the_config_file() {
+        // arrange
+        let file = config_file("[pokemon]\nport = 8080\n");
+        let vars = vars(vec![(
+            "SHAKESPEAREAN_CONFIG_FILE",
+            file.path().to_str().unwrap(),
+        )]);
+
+        // act
+        let problems = problems(Config::from_vars(vars, vec![]));
+
+        // assert
+        assert_eq!(
+            problems,
+            vec!["pokemon must be a string, number, boolean or array"]
+        );
+    }
+
+    #[test]
+    fn it_redacts_the_api_token_from_debug_output() {
+        // arrange
+        let config =
+            Config::from_vars(vars(vec![("SHAKESPEAREAN_API_TOKEN", "hunter2")]), vec![]).unwrap();
+
+        // act
+        let debug = format!("{:#?}", config);
+
+        // assert
+        assert!(!debug.contains("hunter2"));
+        assert!(debug.contains("[redacted]"));
+    }
+
+    #[test]
+    fn it_loads_the_api_token_from_a_file() {
+        // arrange
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "hunter2").unwrap();
+        let path = file.path().to_str().unwrap().to_string();
+        let config =
+            Config::from_vars(vars(vec![("SHAKESPEAREAN_API_TOKEN_FILE", &path)]), vec![]).unwrap();
+
+        // act
+        let api_token = config.api_token().unwrap();
+
+        // assert
+        assert_eq!(api_token.unwrap().expose(), "hunter2");
+    }
+
+    #[test]
+    fn it_rejects_setting_the_api_token_twice() {
+        // arrange
+        let vars = vars(vec![
+            ("SHAKESPEAREAN_API_TOKEN", "hunter2"),
+            ("SHAKESPEAREAN_API_TOKEN_FILE", "/run/secrets/token"),
+        ]);
+
+        // act
+        let problems = problems(Config::from_vars(vars, vec![]));
+
+        // assert
+        assert_eq!(
+            problems,
+            vec!["api_token and api_token_file can't both be set"]
+        );
+    }
+}
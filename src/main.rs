@@ -1,33 +1,202 @@
+mod cli;
 mod client;
+mod config;
+mod metrics;
+mod readiness;
+mod request_context;
 mod routes;
-use client::{pokemon_client::PokemonClient, translation_client::TranslationClient};
+mod rules;
+mod secret;
+mod shutdown;
+mod telemetry;
+use funtranslations_client::{TranslationCache, TranslationClient, TranslationType};
+use pokeapi_client::{PokemonClient, RetryPolicy};
 
-use lazy_static::lazy_static;
-use serde::Deserialize;
+use clap::Parser;
+use cli::{Cli, Command};
+use config::Config;
+use metrics::Metrics;
+use readiness::Readiness;
+use routes::PokemonResponse;
+use rules::TranslationRules;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info};
 
-#[derive(Deserialize, Debug)]
-pub struct Config {
-    port: u16,
-    api_token: Option<String>,
-    pokemon_api_base_url: String,
-    translation_api_base_url: String,
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    let config = match Config::load(cli.overrides()) {
+        Ok(config) => config,
+        Err(error) => exit_with_error(error),
+    };
+    match cli.command {
+        None | Some(Command::Serve { .. }) => serve(config).await,
+        Some(Command::Lookup { name }) => lookup(config, &name).await,
+        Some(Command::Translate { style, text, .. }) => translate(config, style, &text).await,
+    }
 }
 
-lazy_static! {
-    pub static ref CONFIG: Config = {
-        match envy::from_env::<Config>() {
-            Ok(config) => config,
-            Err(error) => panic!("{:#?}", error),
-        }
+fn exit_with_error(error: impl std::fmt::Display) -> ! {
+    eprintln!("{}", error);
+    std::process::exit(1);
+}
+
+/// A pokeapi client as configured, without the cache or an observer.
+fn pokemon_client(config: &Config) -> Result<PokemonClient, String> {
+    PokemonClient::new(config.pokemon_api_base_url.clone())
+        .with_retry_policy(RetryPolicy {
+            max_attempts: config.pokemon_retry_max_attempts,
+            base_delay: Duration::from_millis(config.pokemon_retry_base_delay_ms),
+            jitter: Duration::from_millis(config.pokemon_retry_jitter_ms),
+            retryable_statuses: config.pokemon_retry_statuses.clone(),
+            ..RetryPolicy::default()
+        })
+        .with_timeouts(pokeapi_client::Timeouts {
+            connect: Duration::from_millis(config.pokemon_api_connect_timeout_ms),
+            total: Duration::from_millis(config.pokemon_api_timeout_ms),
+        })
+        .map_err(|error| error.to_string())
+}
+
+/// A funtranslations client as configured, without the cache or an observer.
+fn translation_client(config: &Config) -> Result<TranslationClient, String> {
+    let api_token = config.api_token()?;
+    Ok(TranslationClient::new(
+        config.translation_api_base_url.clone(),
+        api_token.map(|token| token.expose().to_string()),
+    )
+    .with_rate_limit(
+        config.translation_rate_limit_requests,
+        Duration::from_secs(config.translation_rate_limit_period_secs),
+    )
+    .with_max_retry_after(Duration::from_secs(config.translation_max_retry_after_secs))
+    .with_circuit_breaker(
+        config.translation_circuit_failure_threshold,
+        Duration::from_secs(config.translation_circuit_cool_down_secs),
+    )
+    .with_timeouts(funtranslations_client::Timeouts {
+        connect: Duration::from_millis(config.translation_api_connect_timeout_ms),
+        total: Duration::from_millis(config.translation_api_timeout_ms),
+    })
+    .map_err(|error| error.to_string())?
+    .with_local_translation(config.translation_local))
+}
+
+/// Prints a pokemon as served by `/pokemon/<name>`.
+async fn lookup(config: Config, name: &str) {
+    let client = match pokemon_client(&config) {
+        Ok(client) => client,
+        Err(error) => exit_with_error(error),
     };
+    match client.get_pokemon(name).await {
+        Ok(pokemon) => match serde_json::to_string_pretty(&PokemonResponse::from(pokemon)) {
+            Ok(json) => println!("{}", json),
+            Err(error) => exit_with_error(error),
+        },
+        Err(error) => exit_with_error(error),
+    }
 }
 
-#[tokio::main]
-async fn main() {
-    println!("Starting server on port {}", CONFIG.port);
+/// Prints `text` translated by funtranslations, or offline if `translation_local` allows it.
+async fn translate(config: Config, style: TranslationType, text: &str) {
+    let client = match translation_client(&config) {
+        Ok(client) => client,
+        Err(error) => exit_with_error(error),
+    };
+    match client.get_translation(text, style).await {
+        Ok(translation) => println!("{}", translation),
+        Err(error) => exit_with_error(error),
+    }
+}
+
+/// Runs the server until it's shut down by a signal.
+async fn serve(config: Config) {
+    telemetry::init(config.log_format);
+    info!(port = config.port, "Starting server");
+    let metrics = Arc::new(Metrics::new());
+
+    let mut translation_client = match translation_client(&config) {
+        Ok(client) => client,
+        Err(error) => exit_with_error(error),
+    }
+    .with_observer(metrics.upstream_observer("funtranslations"));
+    // Kept to flush on shutdown, clones share the database
+    let translation_cache =
+        config
+            .translation_cache_path
+            .as_ref()
+            .map(|path| match TranslationCache::open(path) {
+                Ok(cache) => cache,
+                Err(error) => exit_with_error(format!(
+                    "Failed to open translation cache {}: {}",
+                    path, error
+                )),
+            });
+    if let Some(cache) = &translation_cache {
+        translation_client = translation_client.with_cache(cache.clone());
+    }
+
+    let translation_rules = match &config.translation_rules_path {
+        Some(path) => match TranslationRules::load(path) {
+            Ok(rules) => rules,
+            Err(error) => exit_with_error(format!(
+                "Failed to load translation rules {}: {}",
+                path, error
+            )),
+        },
+        None => TranslationRules::default(),
+    };
+
+    let readiness = Arc::new(Readiness::new(Duration::from_secs(
+        config.readiness_cache_ttl_secs,
+    )));
+    let pokemon_client = match pokemon_client(&config) {
+        Ok(client) => client,
+        Err(error) => exit_with_error(error),
+    };
     let routes = crate::routes::routes(
-        PokemonClient::new(CONFIG.pokemon_api_base_url.clone()),
-        TranslationClient::new(CONFIG.translation_api_base_url.clone(), None),
+        Arc::new(
+            pokemon_client
+                .with_cache(
+                    Duration::from_secs(config.pokemon_cache_ttl_secs),
+                    config.pokemon_cache_max_entries,
+                )
+                .with_observer(metrics.upstream_observer("pokeapi")),
+        ),
+        Arc::new(translation_client),
+        translation_rules,
+        metrics,
+        readiness.clone(),
     );
-    warp::serve(routes).run(([0, 0, 0, 0], CONFIG.port)).await;
+
+    let (addr, server) = match shutdown::serve(
+        routes,
+        ([0, 0, 0, 0], config.port).into(),
+        shutdown::fail_readiness(
+            shutdown::signal(),
+            readiness,
+            Duration::from_millis(config.shutdown_readiness_delay_ms),
+        ),
+        Duration::from_millis(config.shutdown_drain_timeout_ms),
+    ) {
+        Ok(server) => server,
+        Err(error) => exit_with_error(format!(
+            "Failed to listen on port {}: {}",
+            config.port, error
+        )),
+    };
+    info!(%addr, "Listening");
+    let drained = server.await;
+
+    if let Some(cache) = translation_cache {
+        if let Err(error) = cache.flush() {
+            error!(%error, "Failed to flush the translation cache");
+        }
+    }
+    if !drained {
+        error!("Stopped before every in flight request finished");
+        std::process::exit(1);
+    }
+    info!("Stopped");
 }
@@ -0,0 +1,222 @@
+use crate::client::client_error::ClientError;
+use crate::routes::route_name;
+use funtranslations_client::{TranslationError, TranslationObserver};
+use pokeapi_client::{PokemonError, PokemonObserver};
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use warp::http::StatusCode;
+
+/// Prometheus metrics for requests to the api, requests to upstreams, translation fallbacks and caches.
+pub struct Metrics {
+    registry: Registry,
+    http_requests: IntCounterVec,
+    http_request_duration: HistogramVec,
+    upstream_requests: IntCounterVec,
+    upstream_request_duration: HistogramVec,
+    translation_fallbacks: IntCounterVec,
+    cache_lookups: IntCounterVec,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let http_requests = IntCounterVec::new(
+            Opts::new(
+                "http_requests_total",
+                "Requests handled, by route and status",
+            ),
+            &["route", "status"],
+        )
+        .unwrap();
+        let http_request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "Time taken to handle requests, by route and status",
+            ),
+            &["route", "status"],
+        )
+        .unwrap();
+        let upstream_requests = IntCounterVec::new(
+            Opts::new(
+                "upstream_requests_total",
+                "Requests sent to upstream apis, by client and result",
+            ),
+            &["client", "result"],
+        )
+        .unwrap();
+        let upstream_request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "upstream_request_duration_seconds",
+                "Time taken by requests to upstream apis, by client and result",
+            ),
+            &["client", "result"],
+        )
+        .unwrap();
+        let translation_fallbacks = IntCounterVec::new(
+            Opts::new(
+                "translation_fallbacks_total",
+                "Descriptions returned untranslated because translating failed, by reason",
+            ),
+            &["reason"],
+        )
+        .unwrap();
+        let cache_lookups = IntCounterVec::new(
+            Opts::new("cache_lookups_total", "Cache lookups, by client and result"),
+            &["client", "result"],
+        )
+        .unwrap();
+
+        let registry = Registry::new();
+        registry.register(Box::new(http_requests.clone())).unwrap();
+        registry
+            .register(Box::new(http_request_duration.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(upstream_requests.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(upstream_request_duration.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(translation_fallbacks.clone()))
+            .unwrap();
+        registry.register(Box::new(cache_lookups.clone())).unwrap();
+
+        Self {
+            registry,
+            http_requests,
+            http_request_duration,
+            upstream_requests,
+            upstream_request_duration,
+            translation_fallbacks,
+            cache_lookups,
+        }
+    }
+
+    pub fn observe_request(&self, path: &str, status: StatusCode, duration: Duration) {
+        let labels = [route_name(path), status.as_str()];
+        self.http_requests.with_label_values(&labels).inc();
+        self.http_request_duration
+            .with_label_values(&labels)
+            .observe(duration.as_secs_f64());
+    }
+
+    pub fn observe_upstream(&self, client: &str, duration: Duration, error: Option<&ClientError>) {
+        let labels = [client, error.map_or("ok", ClientError::kind)];
+        self.upstream_requests.with_label_values(&labels).inc();
+        self.upstream_request_duration
+            .with_label_values(&labels)
+            .observe(duration.as_secs_f64());
+    }
+
+    pub fn observe_fallback(&self, reason: &str) {
+        self.translation_fallbacks
+            .with_label_values(&[reason])
+            .inc();
+    }
+
+    pub fn observe_cache_lookup(&self, client: &str, hit: bool) {
+        let result = if hit { "hit" } else { "miss" };
+        self.cache_lookups
+            .with_label_values(&[client, result])
+            .inc();
+    }
+
+    /// Observes a client's upstream requests and cache lookups, labelled with `client`.
+    pub fn upstream_observer(self: &Arc<Self>, client: &'static str) -> Arc<UpstreamObserver> {
+        Arc::new(UpstreamObserver {
+            metrics: self.clone(),
+            client,
+        })
+    }
+
+    /// Every metric in the prometheus text format.
+    pub fn render(&self) -> String {
+        let mut buffer = vec![];
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct UpstreamObserver {
+    metrics: Arc<Metrics>,
+    client: &'static str,
+}
+
+impl PokemonObserver for UpstreamObserver {
+    fn on_request(&self, duration: Duration, error: Option<&PokemonError>) {
+        let error = error.cloned().map(ClientError::from);
+        self.metrics
+            .observe_upstream(self.client, duration, error.as_ref());
+    }
+
+    fn on_cache_lookup(&self, hit: bool) {
+        self.metrics.observe_cache_lookup(self.client, hit);
+    }
+}
+
+impl TranslationObserver for UpstreamObserver {
+    fn on_request(&self, duration: Duration, error: Option<&TranslationError>) {
+        let error = error.cloned().map(ClientError::from);
+        self.metrics
+            .observe_upstream(self.client, duration, error.as_ref());
+    }
+
+    fn on_cache_lookup(&self, hit: bool) {
+        self.metrics.observe_cache_lookup(self.client, hit);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_labels_upstream_requests_by_error() {
+        // arrange
+        let metrics = Arc::new(Metrics::new());
+        let observer = metrics.upstream_observer("pokeapi");
+
+        // act
+        PokemonObserver::on_request(&*observer, Duration::from_millis(5), None);
+        PokemonObserver::on_request(
+            &*observer,
+            Duration::from_millis(5),
+            Some(&PokemonError::NotFound),
+        );
+        PokemonObserver::on_cache_lookup(&*observer, true);
+
+        // assert
+        let rendered = metrics.render();
+        assert!(rendered.contains("upstream_requests_total{client=\"pokeapi\",result=\"ok\"} 1"));
+        assert!(rendered.contains(
+            "upstream_requests_total{client=\"pokeapi\",result=\"PokemonNotFoundError\"} 1"
+        ));
+        assert!(rendered.contains("cache_lookups_total{client=\"pokeapi\",result=\"hit\"} 1"));
+    }
+
+    #[test]
+    fn it_counts_fallbacks_by_reason() {
+        // arrange
+        let metrics = Metrics::new();
+
+        // act
+        metrics.observe_fallback("rate-limited");
+        metrics.observe_fallback("rate-limited");
+
+        // assert
+        assert!(metrics
+            .render()
+            .contains("translation_fallbacks_total{reason=\"rate-limited\"} 2"));
+    }
+}
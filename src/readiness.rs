@@ -0,0 +1,273 @@
+use crate::client::{pokemon_source::PokemonSource, translator::Translator};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Whether the server can serve traffic.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ReadinessStatus {
+    /// Every dependency is up.
+    Ready,
+    /// Translations are down, so translated descriptions fall back to the standard description.
+    Degraded,
+    /// Pokemon can't be looked up.
+    NotReady,
+    /// The server is draining in flight requests before it exits.
+    ShuttingDown,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum DependencyStatus {
+    Up,
+    Down,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct DependencyReport {
+    pub status: DependencyStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl<E: ToString> From<Result<(), E>> for DependencyReport {
+    fn from(result: Result<(), E>) -> Self {
+        match result {
+            Ok(()) => Self {
+                status: DependencyStatus::Up,
+                error: None,
+            },
+            Err(error) => Self {
+                status: DependencyStatus::Down,
+                error: Some(error.to_string()),
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Dependencies {
+    pub pokeapi: DependencyReport,
+    pub funtranslations: DependencyReport,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ReadinessReport {
+    pub status: ReadinessStatus,
+    /// Not checked while shutting down
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dependencies: Option<Dependencies>,
+}
+
+impl ReadinessReport {
+    fn new(dependencies: Dependencies) -> Self {
+        let status = match (
+            dependencies.pokeapi.status,
+            dependencies.funtranslations.status,
+        ) {
+            (DependencyStatus::Down, _) => ReadinessStatus::NotReady,
+            (DependencyStatus::Up, DependencyStatus::Down) => ReadinessStatus::Degraded,
+            (DependencyStatus::Up, DependencyStatus::Up) => ReadinessStatus::Ready,
+        };
+        Self {
+            status,
+            dependencies: Some(dependencies),
+        }
+    }
+}
+
+/// Checks the upstreams are healthy, caching the result for a short window so frequent probes
+/// don't add load to them.
+pub struct Readiness {
+    cache_ttl: Duration,
+    cached: Mutex<Option<(Instant, ReadinessReport)>>,
+    shutting_down: AtomicBool,
+}
+
+impl Readiness {
+    pub fn new(cache_ttl: Duration) -> Self {
+        Self {
+            cache_ttl,
+            cached: Mutex::new(None),
+            shutting_down: AtomicBool::new(false),
+        }
+    }
+
+    /// Fails every check from now on, so traffic is sent elsewhere while the server shuts down.
+    pub fn start_shutdown(&self) {
+        self.shutting_down.store(true, Ordering::SeqCst);
+    }
+
+    /// Checks both upstreams at once, unless they were checked within the cache window.
+    /// Concurrent checks wait for the one in progress rather than making their own.
+    pub async fn check(
+        &self,
+        pokemon_source: &dyn PokemonSource,
+        translator: &dyn Translator,
+    ) -> ReadinessReport {
+        if self.shutting_down.load(Ordering::SeqCst) {
+            return ReadinessReport {
+                status: ReadinessStatus::ShuttingDown,
+                dependencies: None,
+            };
+        }
+
+        let mut cached = self.cached.lock().await;
+        if let Some((checked_at, report)) = &*cached {
+            if checked_at.elapsed() < self.cache_ttl {
+                return report.clone();
+            }
+        }
+
+        let (pokeapi, funtranslations) =
+            tokio::join!(pokemon_source.check_health(), translator.check_health());
+        let report = ReadinessReport::new(Dependencies {
+            pokeapi: pokeapi.into(),
+            funtranslations: funtranslations.into(),
+        });
+        *cached = Some((Instant::now(), report.clone()));
+        report
+    }
+}
+
+impl Default for Readiness {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(5))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::client_error::ClientError;
+    use crate::request_context::RequestContext;
+    use async_trait::async_trait;
+    use pokeapi_client::Pokemon;
+    use std::sync::atomic::AtomicUsize;
+
+    struct FakePokemonSource {
+        health: Result<(), ClientError>,
+        checks: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl PokemonSource for FakePokemonSource {
+        async fn get_species(
+            &self,
+            _name: &str,
+            _context: &RequestContext,
+        ) -> Result<Pokemon, ClientError> {
+            Err(ClientError::PokemonNotFoundError)
+        }
+
+        async fn check_health(&self) -> Result<(), ClientError> {
+            self.checks.fetch_add(1, Ordering::SeqCst);
+            self.health.clone()
+        }
+    }
+
+    struct FakeTranslator(Result<(), ClientError>);
+
+    #[async_trait]
+    impl Translator for FakeTranslator {
+        async fn translate(
+            &self,
+            _text: &str,
+            _translation_type: funtranslations_client::TranslationType,
+            _context: &RequestContext,
+        ) -> Result<funtranslations_client::Translation, ClientError> {
+            Err(ClientError::TranslationUnsupportedError)
+        }
+
+        async fn check_health(&self) -> Result<(), ClientError> {
+            self.0.clone()
+        }
+    }
+
+    fn pokemon_source(health: Result<(), ClientError>) -> FakePokemonSource {
+        FakePokemonSource {
+            health,
+            checks: AtomicUsize::new(0),
+        }
+    }
+
+    #[tokio::test]
+    async fn it_reports_the_status_of_each_dependency() {
+        let cases = vec![
+            (Ok(()), Ok(()), ReadinessStatus::Ready),
+            (
+                Ok(()),
+                Err(ClientError::TranslationTimeout),
+                ReadinessStatus::Degraded,
+            ),
+            (
+                Err(ClientError::PokemonAPITimeout),
+                Ok(()),
+                ReadinessStatus::NotReady,
+            ),
+            (
+                Err(ClientError::PokemonAPITimeout),
+                Err(ClientError::TranslationTimeout),
+                ReadinessStatus::NotReady,
+            ),
+        ];
+
+        for (pokeapi, funtranslations, status) in cases {
+            // arrange
+            let readiness = Readiness::new(Duration::from_secs(60));
+
+            // act
+            let report = readiness
+                .check(
+                    &pokemon_source(pokeapi.clone()),
+                    &FakeTranslator(funtranslations.clone()),
+                )
+                .await;
+
+            // assert
+            assert_eq!(report.status, status);
+            let dependencies = report.dependencies.unwrap();
+            assert_eq!(dependencies.pokeapi, pokeapi.into());
+            assert_eq!(dependencies.funtranslations, funtranslations.into());
+        }
+    }
+
+    #[tokio::test]
+    async fn it_caches_the_report() {
+        // arrange
+        let readiness = Readiness::new(Duration::from_millis(50));
+        let pokemon_source = pokemon_source(Ok(()));
+        let translator = FakeTranslator(Ok(()));
+
+        // act
+        readiness.check(&pokemon_source, &translator).await;
+        readiness.check(&pokemon_source, &translator).await;
+        let checks_within_window = pokemon_source.checks.load(Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        readiness.check(&pokemon_source, &translator).await;
+
+        // assert
+        assert_eq!(checks_within_window, 1);
+        assert_eq!(pokemon_source.checks.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn it_fails_without_checking_once_shutting_down() {
+        // arrange
+        let readiness = Readiness::new(Duration::from_secs(60));
+        let pokemon_source = pokemon_source(Ok(()));
+        let translator = FakeTranslator(Ok(()));
+        readiness.check(&pokemon_source, &translator).await;
+
+        // act
+        readiness.start_shutdown();
+        let report = readiness.check(&pokemon_source, &translator).await;
+
+        // assert
+        assert_eq!(report.status, ReadinessStatus::ShuttingDown);
+        assert_eq!(report.dependencies, None);
+        assert_eq!(pokemon_source.checks.load(Ordering::SeqCst), 1);
+    }
+}
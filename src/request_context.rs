@@ -0,0 +1,150 @@
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// Longer request ids are replaced, so callers can't fill our logs.
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+/// Identifies an incoming request, so it can be correlated with the requests it makes upstream.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestContext {
+    request_id: String,
+    trace_id: String,
+    span_id: String,
+    trace_flags: String,
+}
+
+impl RequestContext {
+    /// Accepts the caller's `X-Request-Id` and W3C `traceparent`, generating them when they are
+    /// missing or invalid. Upstream requests are made as a new span in the caller's trace.
+    pub fn new(request_id: Option<&str>, traceparent: Option<&str>) -> Self {
+        let request_id = request_id
+            .filter(|id| is_valid_request_id(id))
+            .map(String::from)
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        let (trace_id, trace_flags) = traceparent
+            .and_then(parse_traceparent)
+            .unwrap_or_else(|| (Uuid::new_v4().simple().to_string(), "00".into()));
+        Self {
+            request_id,
+            trace_id,
+            span_id: format!("{:016x}", Uuid::new_v4().as_u64_pair().0),
+            trace_flags,
+        }
+    }
+
+    pub fn request_id(&self) -> &str {
+        &self.request_id
+    }
+
+    pub fn trace_id(&self) -> &str {
+        &self.trace_id
+    }
+
+    pub fn traceparent(&self) -> String {
+        format!("00-{}-{}-{}", self.trace_id, self.span_id, self.trace_flags)
+    }
+
+    /// The headers to forward upstream and echo in the response.
+    pub fn headers(&self) -> Vec<(&'static str, String)> {
+        vec![
+            (REQUEST_ID_HEADER, self.request_id.clone()),
+            (TRACEPARENT_HEADER, self.traceparent()),
+        ]
+    }
+}
+
+fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LENGTH
+        && id.bytes().all(|byte| byte.is_ascii_graphic())
+}
+
+/// Returns the trace id and flags of a version 00 `traceparent`.
+fn parse_traceparent(traceparent: &str) -> Option<(String, String)> {
+    let parts: Vec<&str> = traceparent.trim().split('-').collect();
+    match parts.as_slice() {
+        ["00", trace_id, parent_id, flags]
+            if is_hex_id(trace_id, 32) && is_hex_id(parent_id, 16) && is_hex_id(flags, 2) =>
+        {
+            // All zero ids are invalid, but all zero flags aren't
+            let is_zero = |id: &str| id.bytes().all(|byte| byte == b'0');
+            if is_zero(trace_id) || is_zero(parent_id) {
+                None
+            } else {
+                Some((trace_id.to_string(), flags.to_string()))
+            }
+        }
+        _ => None,
+    }
+}
+
+fn is_hex_id(id: &str, length: usize) -> bool {
+    id.len() == length
+        && id
+            .bytes()
+            .all(|byte| byte.is_ascii_digit() || (b'a'..=b'f').contains(&byte))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    #[test]
+    fn it_accepts_the_callers_request_id_and_trace() {
+        // act
+        let context = RequestContext::new(Some("abc-123"), Some(TRACEPARENT));
+
+        // assert
+        assert_eq!(context.request_id(), "abc-123");
+        assert_eq!(context.trace_id(), "4bf92f3577b34da6a3ce929d0e0e4736");
+        let traceparent = context.traceparent();
+        assert!(traceparent.starts_with("00-4bf92f3577b34da6a3ce929d0e0e4736-"));
+        assert!(traceparent.ends_with("-01"));
+        assert_ne!(traceparent, TRACEPARENT);
+    }
+
+    #[test]
+    fn it_generates_missing_ids() {
+        // act
+        let context = RequestContext::new(None, None);
+
+        // assert
+        assert!(Uuid::parse_str(context.request_id()).is_ok());
+        assert!(parse_traceparent(&context.traceparent()).is_some());
+        assert_ne!(context, RequestContext::new(None, None));
+    }
+
+    #[test]
+    fn it_replaces_invalid_ids() {
+        let long_id = "a".repeat(MAX_REQUEST_ID_LENGTH + 1);
+        let invalid_request_ids = vec!["", "abc 123", "abc\n123", long_id.as_str()];
+        let invalid_traceparents = vec![
+            "",
+            "01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+        ];
+
+        for request_id in invalid_request_ids {
+            // act
+            let context = RequestContext::new(Some(request_id), None);
+
+            // assert
+            assert_ne!(context.request_id(), request_id);
+        }
+
+        for traceparent in invalid_traceparents {
+            // act
+            let context = RequestContext::new(None, Some(traceparent));
+
+            // assert
+            assert!(!traceparent.contains(context.trace_id()), "{}", traceparent);
+        }
+    }
+}
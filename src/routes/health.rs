@@ -0,0 +1,198 @@
+use crate::client::{pokemon_source::PokemonSource, translator::Translator};
+use crate::readiness::{Readiness, ReadinessStatus};
+use serde::Serialize;
+use std::convert::Infallible;
+use std::sync::Arc;
+use warp::http::StatusCode;
+
+#[derive(Serialize)]
+struct LivenessResponse {
+    status: &'static str,
+}
+
+/// The process is up, without checking any upstreams.
+pub async fn live() -> Result<impl warp::Reply, Infallible> {
+    Ok(warp::reply::json(&LivenessResponse { status: "ok" }))
+}
+
+/// Ready or degraded respond with 200, so traffic keeps flowing while translations are down.
+pub async fn ready(
+    readiness: Arc<Readiness>,
+    pokemon_source: Arc<dyn PokemonSource>,
+    translator: Arc<dyn Translator>,
+) -> Result<impl warp::Reply, Infallible> {
+    let report = readiness
+        .check(pokemon_source.as_ref(), translator.as_ref())
+        .await;
+    let status = match report.status {
+        ReadinessStatus::Ready | ReadinessStatus::Degraded => StatusCode::OK,
+        ReadinessStatus::NotReady | ReadinessStatus::ShuttingDown => {
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+    };
+    Ok(warp::reply::with_status(warp::reply::json(&report), status))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::readiness::Readiness;
+    use pokeapi_client::{Habitat, Pokemon};
+    use serde_json::json;
+    use std::sync::Arc;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn ditto() -> Pokemon {
+        Pokemon {
+            id: 132,
+            name: "ditto".into(),
+            is_legendary: false,
+            is_mythical: false,
+            generation: None,
+            color: None,
+            habitat: Habitat {
+                name: "urban".into(),
+                url: "https://pokeapi.co/api/v2/pokemon-habitat/8/".into(),
+            },
+            flavor_text_entries: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn it_is_live_without_upstreams() {
+        // arrange
+        let filter = crate::routes::test_routes("http://127.0.0.1:9", "http://127.0.0.1:9").build();
+
+        // act
+        let res = warp::test::request()
+            .method("GET")
+            .path("/healthz")
+            .reply(&filter)
+            .await;
+
+        // assert
+        assert_eq!(res.status(), 200);
+        assert_eq!(res.body(), "{\"status\":\"ok\"}");
+    }
+
+    #[tokio::test]
+    async fn it_is_ready_when_both_upstreams_are_up() {
+        // arrange
+        let pokemon_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/ditto"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(ditto())))
+            .expect(1)
+            .mount(&pokemon_server)
+            .await;
+        let translation_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&translation_server)
+            .await;
+
+        let filter =
+            crate::routes::test_routes(&pokemon_server.uri(), &translation_server.uri()).build();
+
+        // act
+        let res = warp::test::request()
+            .method("GET")
+            .path("/readyz")
+            .reply(&filter)
+            .await;
+
+        // assert
+        assert_eq!(res.status(), 200);
+        assert_eq!(
+            res.body(),
+            "{\"status\":\"ready\",\"dependencies\":{\"pokeapi\":{\"status\":\"up\"},\"funtranslations\":{\"status\":\"up\"}}}"
+        );
+    }
+
+    #[tokio::test]
+    async fn it_is_degraded_when_only_translations_are_down() {
+        // arrange
+        let pokemon_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/ditto"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(ditto())))
+            .mount(&pokemon_server)
+            .await;
+
+        let filter =
+            crate::routes::test_routes(&pokemon_server.uri(), "http://127.0.0.1:9").build();
+
+        // act
+        let res = warp::test::request()
+            .method("GET")
+            .path("/readyz")
+            .reply(&filter)
+            .await;
+
+        // assert
+        assert_eq!(res.status(), 200);
+        let body: serde_json::Value = serde_json::from_slice(res.body()).unwrap();
+        assert_eq!(body["status"], "degraded");
+        assert_eq!(body["dependencies"]["pokeapi"]["status"], "up");
+        assert_eq!(body["dependencies"]["funtranslations"]["status"], "down");
+        assert!(body["dependencies"]["funtranslations"]["error"]
+            .as_str()
+            .unwrap()
+            .starts_with("Failed to get translation"));
+    }
+
+    #[tokio::test]
+    async fn it_is_not_ready_when_pokeapi_is_down() {
+        // arrange
+        let pokemon_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/ditto"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&pokemon_server)
+            .await;
+        let translation_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&translation_server)
+            .await;
+
+        let filter =
+            crate::routes::test_routes(&pokemon_server.uri(), &translation_server.uri()).build();
+
+        // act
+        let res = warp::test::request()
+            .method("GET")
+            .path("/readyz")
+            .reply(&filter)
+            .await;
+
+        // assert
+        assert_eq!(res.status(), 503);
+        assert_eq!(
+            res.body(),
+            "{\"status\":\"notReady\",\"dependencies\":{\"pokeapi\":{\"status\":\"down\",\"error\":\"Failed to get pokemon: status 503\"},\"funtranslations\":{\"status\":\"up\"}}}"
+        );
+    }
+
+    #[tokio::test]
+    async fn it_is_not_ready_once_shutting_down() {
+        // arrange
+        let readiness = Arc::new(Readiness::default());
+        let filter = crate::routes::test_routes("http://127.0.0.1:9", "http://127.0.0.1:9")
+            .with_readiness(readiness.clone())
+            .build();
+
+        // act
+        readiness.start_shutdown();
+        let res = warp::test::request()
+            .method("GET")
+            .path("/readyz")
+            .reply(&filter)
+            .await;
+
+        // assert
+        assert_eq!(res.status(), 503);
+        assert_eq!(res.body(), "{\"status\":\"shuttingDown\"}");
+    }
+}
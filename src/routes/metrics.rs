@@ -0,0 +1,45 @@
+use crate::metrics::Metrics;
+use std::convert::Infallible;
+use std::sync::Arc;
+
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+pub async fn get(metrics: Arc<Metrics>) -> Result<impl warp::Reply, Infallible> {
+    Ok(warp::reply::with_header(
+        metrics.render(),
+        "content-type",
+        PROMETHEUS_CONTENT_TYPE,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn it_reports_requests_by_route_and_status() {
+        // arrange
+        let filter = crate::routes::test_routes("http://127.0.0.1:9", "http://127.0.0.1:9").build();
+        warp::test::request()
+            .method("GET")
+            .path("/status")
+            .reply(&filter)
+            .await;
+
+        // act
+        let res = warp::test::request()
+            .method("GET")
+            .path("/metrics")
+            .reply(&filter)
+            .await;
+
+        // assert
+        assert_eq!(res.status(), 200);
+        assert_eq!(res.headers()["content-type"], PROMETHEUS_CONTENT_TYPE);
+        let body = std::str::from_utf8(res.body()).unwrap();
+        assert!(body.contains("http_requests_total{route=\"status\",status=\"200\"} 1"));
+        assert!(
+            body.contains("http_request_duration_seconds_count{route=\"status\",status=\"200\"} 1")
+        );
+    }
+}
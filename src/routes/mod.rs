@@ -1,32 +1,189 @@
+mod health;
+mod metrics;
 mod pokemon;
+mod problem;
+mod status;
 mod translated;
+mod translations;
 
-use warp::Filter;
+#[cfg(test)]
+mod test_support;
+#[cfg(test)]
+pub use test_support::test_routes;
 
-use crate::client::{
-    pokemon_client::{Pokemon, PokemonClient},
-    translation_client::TranslationClient,
-};
+use warp::http::HeaderValue;
+use warp::reply::Response;
+use warp::{Filter, Rejection, Reply};
+
+use crate::client::{pokemon_source::PokemonSource, translator::Translator};
+use crate::metrics::Metrics;
+use crate::readiness::Readiness;
+use crate::request_context::{RequestContext, REQUEST_ID_HEADER, TRACEPARENT_HEADER};
+use crate::rules::TranslationRules;
+use pokeapi_client::Pokemon;
+use problem::Problem;
 use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::future::Future;
+use std::sync::Arc;
+use tracing::{field, info_span, Span};
 
 pub fn routes(
-    pokemon_client: PokemonClient,
-    translation_client: TranslationClient,
-) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
-    let clone_pokemon_client = pokemon_client.clone();
+    pokemon_source: Arc<dyn PokemonSource>,
+    translator: Arc<dyn Translator>,
+    translation_rules: TranslationRules,
+    metrics: Arc<Metrics>,
+    readiness: Arc<Readiness>,
+) -> impl Filter<Extract = impl warp::Reply, Error = Infallible> + Clone {
+    let translation_rules = Arc::new(translation_rules);
+    let clone_pokemon_source = pokemon_source.clone();
     let get_pokemon_route = warp::path!("pokemon" / String)
         .and(warp::get())
-        .and_then(move |name| pokemon::get(clone_pokemon_client.clone(), name));
+        .and(warp::path::full())
+        .and(request_context())
+        .and_then(move |name, path, context: RequestContext| {
+            with_context(
+                context.clone(),
+                pokemon::get(clone_pokemon_source.clone(), name, path, context),
+            )
+        });
+
+    let clone_translator = translator.clone();
+    let get_status_route = warp::path!("status")
+        .and(warp::get())
+        .and_then(move || status::get(clone_translator.clone()));
+
+    let get_translations_route = warp::path!("translations")
+        .and(warp::get())
+        .and_then(translations::get);
+
+    let get_healthz_route = warp::path!("healthz")
+        .and(warp::get())
+        .and_then(health::live);
+
+    let clone_pokemon_source = pokemon_source.clone();
+    let clone_translator = translator.clone();
+    let get_readyz_route = warp::path!("readyz").and(warp::get()).and_then(move || {
+        health::ready(
+            readiness.clone(),
+            clone_pokemon_source.clone(),
+            clone_translator.clone(),
+        )
+    });
 
+    let clone_metrics = metrics.clone();
+    let get_metrics_route = warp::path!("metrics")
+        .and(warp::get())
+        .and_then(move || metrics::get(clone_metrics.clone()));
+
+    let clone_metrics = metrics.clone();
     let get_translated_pokemon = warp::path!("pokemon" / "translated" / String)
         .and(warp::get())
-        .and_then(move |name| {
-            translated::get(pokemon_client.clone(), translation_client.clone(), name)
+        .and(warp::query::<translated::TranslatedQuery>())
+        .and(warp::path::full())
+        .and(request_context())
+        .and_then(move |name, query, path, context: RequestContext| {
+            with_context(
+                context.clone(),
+                translated::get(
+                    pokemon_source.clone(),
+                    translator.clone(),
+                    translation_rules.clone(),
+                    clone_metrics.clone(),
+                    name,
+                    query,
+                    path,
+                    context,
+                ),
+            )
         });
 
     warp::get()
         .and(get_translated_pokemon)
         .or(get_pokemon_route)
+        .or(get_status_route)
+        .or(get_translations_route)
+        .or(get_metrics_route)
+        .or(get_healthz_route)
+        .or(get_readyz_route)
+        .recover(problem::handle_rejection)
+        // Routes that didn't echo a request context, such as unknown routes, get a new one
+        .and(request_context())
+        .map(|reply, context: RequestContext| with_request_context(reply, &context))
+        .with(warp::trace(|info| {
+            info_span!(
+                "request",
+                method = %info.method(),
+                path = info.path(),
+                route = route_name(info.path()),
+                request_id = field::Empty,
+                trace_id = field::Empty,
+                pokemon = field::Empty,
+            )
+        }))
+        .with(warp::log::custom(move |info| {
+            metrics.observe_request(info.path(), info.status(), info.elapsed())
+        }))
+}
+
+/// Reads the request id and trace context from the request headers, generating them if needed.
+fn request_context() -> impl Filter<Extract = (RequestContext,), Error = Infallible> + Clone {
+    warp::header::headers_cloned().map(|headers: warp::http::HeaderMap| {
+        let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+        RequestContext::new(header(REQUEST_ID_HEADER), header(TRACEPARENT_HEADER))
+    })
+}
+
+/// Runs a route handler with the request context on its logs, and echoes the context on the
+/// response, whether the handler succeeds or fails.
+async fn with_context(
+    context: RequestContext,
+    handler: impl Future<Output = Result<impl Reply, Rejection>>,
+) -> Result<Response, Infallible> {
+    record_request_context(&context);
+    let response = match handler.await {
+        Ok(reply) => reply.into_response(),
+        Err(rejection) => Problem::from_rejection(&rejection).into_response(),
+    };
+    Ok(with_request_context(response, &context))
+}
+
+/// Echoes the request id and trace context on the response, unless a route already has.
+fn with_request_context(reply: impl Reply, context: &RequestContext) -> Response {
+    let mut response = reply.into_response();
+    if !response.headers().contains_key(REQUEST_ID_HEADER) {
+        record_request_context(context);
+        for (name, value) in context.headers() {
+            if let Ok(value) = HeaderValue::from_str(&value) {
+                response.headers_mut().insert(name, value);
+            }
+        }
+    }
+    response
+}
+
+fn record_request_context(context: &RequestContext) {
+    let span = Span::current();
+    span.record("request_id", context.request_id());
+    span.record("trace_id", context.trace_id());
+}
+
+/// Groups paths by route, so pokemon names don't end up as metric labels.
+pub fn route_name(path: &str) -> &'static str {
+    if path.starts_with("/pokemon/translated/") {
+        "translated"
+    } else if path.starts_with("/pokemon/") {
+        "pokemon"
+    } else {
+        match path {
+            "/status" => "status",
+            "/translations" => "translations",
+            "/metrics" => "metrics",
+            "/healthz" => "healthz",
+            "/readyz" => "readyz",
+            _ => "other",
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -36,6 +193,24 @@ pub struct PokemonResponse {
     #[serde(rename = "isLegendary")]
     pub is_legendary: bool,
     pub habitat: String,
+    /// Only set on the translated route
+    #[serde(flatten)]
+    pub translation: Option<TranslationInfo>,
+}
+
+/// Describes which translation was applied to the description, if any.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct TranslationInfo {
+    /// The translation applied to the description, or none if it wasn't translated
+    pub translation: Option<String>,
+    /// Where the description was translated, `funtranslations` or `offline`
+    #[serde(rename = "translationSource", skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Whether translating failed, so the standard description was used instead
+    #[serde(rename = "translationFallback")]
+    pub fallback: bool,
+    #[serde(rename = "fallbackReason", skip_serializing_if = "Option::is_none")]
+    pub fallback_reason: Option<String>,
 }
 
 impl From<Pokemon> for PokemonResponse {
@@ -46,6 +221,33 @@ impl From<Pokemon> for PokemonResponse {
             is_legendary: pokemon.is_legendary,
             habitat: pokemon.habitat.name,
             description,
+            translation: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_groups_requests_by_route() {
+        // arrange
+        let cases = vec![
+            ("/pokemon/charizard", "pokemon"),
+            ("/pokemon/translated/charizard", "translated"),
+            ("/status", "status"),
+            ("/metrics", "metrics"),
+            ("/readyz", "readyz"),
+            ("/missingno", "other"),
+        ];
+
+        for (path, expected) in cases {
+            // act
+            let route = route_name(path);
+
+            // assert
+            assert_eq!(route, expected, "{}", path);
         }
     }
 }
@@ -1,20 +1,20 @@
-use crate::client::client_error;
-use crate::client::pokemon_client::PokemonClient;
-use serde::{Deserialize, Serialize};
-use std::convert::Infallible;
+use crate::client::pokemon_source::PokemonSource;
+use crate::request_context::RequestContext;
+use std::sync::Arc;
+use tracing::{warn, Span};
+use warp::path::FullPath;
 
+use super::problem;
 use super::PokemonResponse;
 
-#[derive(Serialize, Deserialize)]
-struct GetPokemonErrorOutput {
-    error: String,
-}
-
 pub async fn get(
-    pokemon_client: PokemonClient,
+    pokemon_source: Arc<dyn PokemonSource>,
     pokemon_name: String,
-) -> Result<impl warp::Reply, Infallible> {
-    let response = pokemon_client.get_pokemon(&pokemon_name).await;
+    path: FullPath,
+    context: RequestContext,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    Span::current().record("pokemon", pokemon_name.as_str());
+    let response = pokemon_source.get_species(&pokemon_name, &context).await;
     match response {
         Ok(pokemon) => {
             let pokemon_response = PokemonResponse::from(pokemon);
@@ -23,40 +23,21 @@ pub async fn get(
                 warp::http::StatusCode::OK,
             ))
         }
-        Err(pokemon_error) => match pokemon_error {
-            client_error::ClientError::PokemonNotFoundError => Ok(warp::reply::with_status(
-                warp::reply::json(&GetPokemonErrorOutput {
-                    error: "Failed to find pokemon".into(),
-                }),
-                warp::http::StatusCode::NOT_FOUND,
-            )),
-            _ => Ok(warp::reply::with_status(
-                warp::reply::json(&GetPokemonErrorOutput {
-                    error: "Failed to get pokemon".into(),
-                }),
-                warp::http::StatusCode::INTERNAL_SERVER_ERROR,
-            )),
-        },
+        Err(pokemon_error) => {
+            warn!(error = %pokemon_error, "Failed to get pokemon");
+            Err(problem::reject(pokemon_error, path.as_str().into()))
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
-    use crate::client::{
-        pokemon_client::{FlavorTextEntry, Habitat, Language, Pokemon},
-        translation_client::TranslationClient,
-    };
+    use pokeapi_client::{FlavorTextEntry, Habitat, Language, Pokemon};
     use serde_json::json;
 
-    use wiremock::matchers::{method, path};
+    use wiremock::matchers::{header_exists, method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
-    #[derive(Serialize, Deserialize, Debug, PartialEq)]
-    struct TextInput {
-        text: String,
-    }
     #[tokio::test]
     async fn it_successfully_get_a_pokemon() {
         // arrange
@@ -73,6 +54,9 @@ mod tests {
 				},
             }],
             is_legendary: false,
+            is_mythical: false,
+            generation: None,
+            color: None,
             habitat: Habitat {
                 name: "urban".into(),
                 url: "https://pokeapi.co/api/v2/pokemon-habitat/8/".into(),
@@ -89,10 +73,7 @@ mod tests {
             .await;
 
         // act
-        let filter = crate::routes::routes(
-            PokemonClient::new(mock_server.uri()),
-            TranslationClient::new(mock_server.uri(), None),
-        );
+        let filter = crate::routes::test_routes(&mock_server.uri(), &mock_server.uri()).build();
         let res = warp::test::request()
             .method("GET")
             .path("/pokemon/charizard")
@@ -116,10 +97,7 @@ mod tests {
             .await;
 
         // act
-        let filter = crate::routes::routes(
-            PokemonClient::new(mock_server.uri()),
-            TranslationClient::new(mock_server.uri(), None),
-        );
+        let filter = crate::routes::test_routes(&mock_server.uri(), &mock_server.uri()).build();
         let res = warp::test::request()
             .method("GET")
             .path("/pokemon/invalidPokemon")
@@ -128,6 +106,70 @@ mod tests {
 
         // assert
         assert_eq!(res.status(), 404);
-        assert_eq!(res.body(), "{\"error\":\"Failed to find pokemon\"}");
+        assert_eq!(res.headers()["content-type"], "application/problem+json");
+        assert_eq!(res.body(), "{\"type\":\"/problems/pokemon-not-found\",\"title\":\"Pokemon not found\",\"status\":404,\"detail\":\"Failed to find pokemon\",\"instance\":\"/pokemon/invalidPokemon\"}");
+    }
+
+    #[tokio::test]
+    async fn it_returns_502_on_upstream_error() {
+        // arrange
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/charizard"))
+            .respond_with(ResponseTemplate::new(500).set_body_string("Internal Server Error"))
+            .mount(&mock_server)
+            .await;
+
+        // act
+        let filter = crate::routes::test_routes(&mock_server.uri(), &mock_server.uri()).build();
+        let res = warp::test::request()
+            .method("GET")
+            .path("/pokemon/charizard")
+            .reply(&filter)
+            .await;
+
+        // assert
+        assert_eq!(res.status(), 502);
+        assert_eq!(res.headers()["content-type"], "application/problem+json");
+        assert_eq!(res.body(), "{\"type\":\"/problems/pokemon-upstream-error\",\"title\":\"Pokeapi request failed\",\"status\":502,\"detail\":\"Failed to get pokemon: status 500, body \\\"Internal Server Error\\\"\",\"instance\":\"/pokemon/charizard\"}");
+    }
+
+    #[tokio::test]
+    async fn it_generates_a_request_id_and_trace_for_problem_responses() {
+        // arrange
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/missingno"))
+            .and(header_exists("x-request-id"))
+            .and(header_exists("traceparent"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let filter = crate::routes::test_routes(&mock_server.uri(), &mock_server.uri()).build();
+
+        // act
+        let res = warp::test::request()
+            .method("GET")
+            .path("/pokemon/missingno")
+            .reply(&filter)
+            .await;
+
+        // assert
+        assert_eq!(res.status(), 404);
+        let requests = mock_server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 1);
+        for name in &["x-request-id", "traceparent"] {
+            let (_, forwarded) = requests[0]
+                .headers
+                .iter()
+                .find(|(key, _)| key.as_str() == *name)
+                .unwrap();
+            assert_eq!(forwarded, res.headers()[*name].to_str().unwrap());
+        }
+        mock_server.verify().await;
     }
 }
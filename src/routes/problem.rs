@@ -0,0 +1,302 @@
+use crate::client::client_error::ClientError;
+use serde::Serialize;
+use std::convert::Infallible;
+use warp::http::StatusCode;
+use warp::reply::Response;
+use warp::{Rejection, Reply};
+
+const PROBLEM_CONTENT_TYPE: &str = "application/problem+json";
+
+/// An RFC 7807 problem details response body.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct Problem {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+}
+
+impl Problem {
+    pub fn new(
+        slug: &str,
+        title: &str,
+        status: StatusCode,
+        detail: String,
+        instance: Option<String>,
+    ) -> Self {
+        Self {
+            problem_type: format!("/problems/{}", slug),
+            title: title.into(),
+            status: status.as_u16(),
+            detail,
+            instance,
+        }
+    }
+
+    pub fn from_client_error(error: &ClientError, instance: String) -> Self {
+        let (slug, title, status) = match error {
+            ClientError::PokemonNotFoundError => (
+                "pokemon-not-found",
+                "Pokemon not found",
+                StatusCode::NOT_FOUND,
+            ),
+            ClientError::PokemonDeserializationError(_) => (
+                "pokemon-invalid-response",
+                "Invalid response from pokeapi",
+                StatusCode::BAD_GATEWAY,
+            ),
+            ClientError::PokemonAPIError(_) => (
+                "pokemon-upstream-error",
+                "Pokeapi request failed",
+                StatusCode::BAD_GATEWAY,
+            ),
+            ClientError::PokemonAPITimeout => (
+                "pokemon-upstream-timeout",
+                "Pokeapi request timed out",
+                StatusCode::GATEWAY_TIMEOUT,
+            ),
+            ClientError::TranslationDeserializationError(_) => (
+                "translation-invalid-response",
+                "Invalid response from funtranslations",
+                StatusCode::BAD_GATEWAY,
+            ),
+            ClientError::TranslationAPIError(_) => (
+                "translation-upstream-error",
+                "Funtranslations request failed",
+                StatusCode::BAD_GATEWAY,
+            ),
+            ClientError::TranslationTooManyRequestsError => (
+                "translation-rate-limited",
+                "Funtranslations rate limit reached",
+                StatusCode::SERVICE_UNAVAILABLE,
+            ),
+            ClientError::TranslationCircuitOpenError => (
+                "translation-unavailable",
+                "Funtranslations is unavailable",
+                StatusCode::SERVICE_UNAVAILABLE,
+            ),
+            ClientError::TranslationTimeout => (
+                "translation-upstream-timeout",
+                "Funtranslations request timed out",
+                StatusCode::GATEWAY_TIMEOUT,
+            ),
+            ClientError::TranslationUnsupportedError => (
+                "translation-unsupported",
+                "Translation not supported offline",
+                StatusCode::NOT_IMPLEMENTED,
+            ),
+        };
+        Self::new(slug, title, status, error.to_string(), Some(instance))
+    }
+
+    fn status_code(&self) -> StatusCode {
+        StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    /// The problem to respond with for a rejection.
+    pub fn from_rejection(rejection: &Rejection) -> Self {
+        if let Some(ProblemRejection(problem)) = rejection.find() {
+            problem.clone()
+        } else if rejection.is_not_found() {
+            Problem::new(
+                "not-found",
+                "Not found",
+                StatusCode::NOT_FOUND,
+                "No route matches the requested path".into(),
+                None,
+            )
+        } else if rejection.find::<warp::reject::MethodNotAllowed>().is_some() {
+            Problem::new(
+                "method-not-allowed",
+                "Method not allowed",
+                StatusCode::METHOD_NOT_ALLOWED,
+                "The requested path doesn't support this method".into(),
+                None,
+            )
+        } else if let Some(problem) = Self::from_bad_request(rejection) {
+            problem
+        } else {
+            tracing::error!(?rejection, "Unhandled rejection");
+            Problem::new(
+                "internal-error",
+                "Internal server error",
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "The request couldn't be handled".into(),
+                None,
+            )
+        }
+    }
+
+    /// The problem for a rejection caused by a malformed request, if it is one.
+    fn from_bad_request(rejection: &Rejection) -> Option<Self> {
+        let (slug, title, status, detail) =
+            if let Some(error) = rejection.find::<warp::reject::InvalidQuery>() {
+                (
+                    "invalid-query",
+                    "Invalid query string",
+                    StatusCode::BAD_REQUEST,
+                    error.to_string(),
+                )
+            } else if let Some(error) = rejection.find::<warp::reject::InvalidHeader>() {
+                (
+                    "invalid-header",
+                    "Invalid request header",
+                    StatusCode::BAD_REQUEST,
+                    error.to_string(),
+                )
+            } else if let Some(error) = rejection.find::<warp::reject::MissingHeader>() {
+                (
+                    "missing-header",
+                    "Missing request header",
+                    StatusCode::BAD_REQUEST,
+                    error.to_string(),
+                )
+            } else if let Some(error) = rejection.find::<warp::reject::UnsupportedMediaType>() {
+                (
+                    "unsupported-media-type",
+                    "Unsupported media type",
+                    StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                    error.to_string(),
+                )
+            } else if let Some(error) = rejection.find::<warp::reject::LengthRequired>() {
+                (
+                    "length-required",
+                    "Length required",
+                    StatusCode::LENGTH_REQUIRED,
+                    error.to_string(),
+                )
+            } else if let Some(error) = rejection.find::<warp::reject::PayloadTooLarge>() {
+                (
+                    "payload-too-large",
+                    "Payload too large",
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    error.to_string(),
+                )
+            } else {
+                return None;
+            };
+        Some(Problem::new(slug, title, status, detail, None))
+    }
+
+    pub fn into_response(self) -> Response {
+        let status = self.status_code();
+        warp::reply::with_header(
+            warp::reply::with_status(warp::reply::json(&self), status),
+            "content-type",
+            PROBLEM_CONTENT_TYPE,
+        )
+        .into_response()
+    }
+}
+
+/// Rejects a request with a problem, to be turned into a problem response.
+#[derive(Debug)]
+pub struct ProblemRejection(pub Problem);
+
+impl warp::reject::Reject for ProblemRejection {}
+
+/// Rejects a request because an upstream client failed.
+pub fn reject(error: ClientError, instance: String) -> Rejection {
+    warp::reject::custom(ProblemRejection(Problem::from_client_error(
+        &error, instance,
+    )))
+}
+
+/// Turns every rejection into an `application/problem+json` response.
+pub async fn handle_rejection(rejection: Rejection) -> Result<impl Reply, Infallible> {
+    Ok(Problem::from_rejection(&rejection).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_maps_client_errors_to_statuses() {
+        // arrange
+        let cases = vec![
+            (ClientError::PokemonNotFoundError, 404),
+            (
+                ClientError::PokemonDeserializationError(Default::default()),
+                502,
+            ),
+            (ClientError::PokemonAPIError(Default::default()), 502),
+            (ClientError::PokemonAPITimeout, 504),
+            (
+                ClientError::TranslationDeserializationError(Default::default()),
+                502,
+            ),
+            (ClientError::TranslationAPIError(Default::default()), 502),
+            (ClientError::TranslationTooManyRequestsError, 503),
+            (ClientError::TranslationCircuitOpenError, 503),
+            (ClientError::TranslationTimeout, 504),
+            (ClientError::TranslationUnsupportedError, 501),
+        ];
+
+        for (error, status) in cases {
+            // act
+            let problem = Problem::from_client_error(&error, "/pokemon/charizard".into());
+
+            // assert
+            assert_eq!(problem.status, status, "{:?}", error);
+            assert_eq!(problem.detail, error.to_string());
+            assert_eq!(problem.instance, Some("/pokemon/charizard".into()));
+        }
+    }
+
+    #[tokio::test]
+    async fn it_returns_a_problem_for_unknown_routes() {
+        // arrange
+        let filter = crate::routes::test_routes("http://127.0.0.1:9", "http://127.0.0.1:9").build();
+
+        // act
+        let res = warp::test::request()
+            .method("GET")
+            .path("/digimon/agumon")
+            .reply(&filter)
+            .await;
+
+        // assert
+        assert_eq!(res.status(), 404);
+        assert_eq!(res.headers()["content-type"], PROBLEM_CONTENT_TYPE);
+        assert!(res.headers().contains_key("x-request-id"));
+        assert!(res.headers().contains_key("traceparent"));
+        assert_eq!(res.body(), "{\"type\":\"/problems/not-found\",\"title\":\"Not found\",\"status\":404,\"detail\":\"No route matches the requested path\"}");
+    }
+
+    #[tokio::test]
+    async fn it_returns_a_bad_request_problem_for_an_invalid_query() {
+        // arrange
+        let filter = crate::routes::test_routes("http://127.0.0.1:9", "http://127.0.0.1:9").build();
+
+        // act
+        let res = warp::test::request()
+            .method("GET")
+            .path("/pokemon/translated/charizard?style=yoda&style=shakespeare")
+            .reply(&filter)
+            .await;
+
+        // assert
+        assert_eq!(res.status(), 400);
+        assert_eq!(res.headers()["content-type"], PROBLEM_CONTENT_TYPE);
+        assert_eq!(res.body(), "{\"type\":\"/problems/invalid-query\",\"title\":\"Invalid query string\",\"status\":400,\"detail\":\"Invalid query string\"}");
+    }
+
+    #[test]
+    fn it_hides_the_details_of_unhandled_rejections() {
+        // arrange
+        #[derive(Debug)]
+        struct Unhandled;
+        impl warp::reject::Reject for Unhandled {}
+
+        // act
+        let problem = Problem::from_rejection(&warp::reject::custom(Unhandled));
+
+        // assert
+        assert_eq!(problem.status, 500);
+        assert_eq!(problem.detail, "The request couldn't be handled");
+    }
+}
@@ -0,0 +1,37 @@
+use crate::client::translator::Translator;
+use funtranslations_client::CircuitState;
+use serde::Serialize;
+use std::convert::Infallible;
+use std::sync::Arc;
+
+#[derive(Serialize)]
+struct StatusResponse {
+    #[serde(rename = "translationCircuitBreaker")]
+    translation_circuit_breaker: CircuitState,
+}
+
+pub async fn get(translator: Arc<dyn Translator>) -> Result<impl warp::Reply, Infallible> {
+    Ok(warp::reply::json(&StatusResponse {
+        translation_circuit_breaker: translator.circuit_state(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    async fn it_reports_the_translation_circuit_breaker_state() {
+        // arrange
+        let filter = crate::routes::test_routes("http://127.0.0.1:9", "http://127.0.0.1:9").build();
+
+        // act
+        let res = warp::test::request()
+            .method("GET")
+            .path("/status")
+            .reply(&filter)
+            .await;
+
+        // assert
+        assert_eq!(res.status(), 200);
+        assert_eq!(res.body(), "{\"translationCircuitBreaker\":\"closed\"}");
+    }
+}
@@ -0,0 +1,60 @@
+use super::routes;
+use crate::client::{pokemon_source::PokemonSource, translator::Translator};
+use crate::metrics::Metrics;
+use crate::readiness::Readiness;
+use crate::rules::TranslationRules;
+use funtranslations_client::TranslationClient;
+use pokeapi_client::PokemonClient;
+use std::convert::Infallible;
+use std::sync::Arc;
+use warp::Filter;
+
+/// The routes for a test, calling pokeapi and funtranslations at the given uris.
+pub fn test_routes(pokemon_uri: &str, translation_uri: &str) -> TestRoutes {
+    TestRoutes {
+        pokemon_source: Arc::new(PokemonClient::new(pokemon_uri.into())),
+        translator: Arc::new(TranslationClient::new(translation_uri.into(), None)),
+        metrics: Arc::new(Metrics::new()),
+        readiness: Arc::new(Readiness::default()),
+    }
+}
+
+/// Builds the routes for a test, replacing the defaults of `test_routes`.
+pub struct TestRoutes {
+    pokemon_source: Arc<dyn PokemonSource>,
+    translator: Arc<dyn Translator>,
+    metrics: Arc<Metrics>,
+    readiness: Arc<Readiness>,
+}
+
+impl TestRoutes {
+    pub fn with_pokemon_source(mut self, pokemon_source: impl PokemonSource + 'static) -> Self {
+        self.pokemon_source = Arc::new(pokemon_source);
+        self
+    }
+
+    pub fn with_translator(mut self, translator: impl Translator + 'static) -> Self {
+        self.translator = Arc::new(translator);
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    pub fn with_readiness(mut self, readiness: Arc<Readiness>) -> Self {
+        self.readiness = readiness;
+        self
+    }
+
+    pub fn build(self) -> impl Filter<Extract = impl warp::Reply, Error = Infallible> + Clone {
+        routes(
+            self.pokemon_source,
+            self.translator,
+            TranslationRules::default(),
+            self.metrics,
+            self.readiness,
+        )
+    }
+}
@@ -1,42 +1,132 @@
 use crate::client::{
-    client_error,
-    pokemon_client::PokemonClient,
-    translation_client::{TranslationClient, TranslationType},
+    client_error::ClientError, pokemon_source::PokemonSource, translator::Translator,
 };
-use serde::{Deserialize, Serialize};
-use std::convert::Infallible;
+use crate::metrics::Metrics;
+use crate::request_context::RequestContext;
+use crate::rules::TranslationRules;
+use funtranslations_client::TranslationType;
+use serde::Deserialize;
+use std::sync::Arc;
+use tracing::{warn, Span};
+use warp::http::StatusCode;
+use warp::path::FullPath;
+
+use super::problem::{self, Problem, ProblemRejection};
+use super::{PokemonResponse, TranslationInfo};
+
+const AUTO_STYLE: &str = "auto";
+
+#[derive(Deserialize)]
+pub struct TranslatedQuery {
+    style: Option<String>,
+}
 
-use super::PokemonResponse;
+/// Which translation the caller asked for. `None` means pick one based on the pokemon.
+fn parse_style(style: Option<&str>) -> Result<Option<TranslationType>, String> {
+    match style {
+        None | Some(AUTO_STYLE) => Ok(None),
+        Some(style) => TranslationType::from_name(style).map(Some).ok_or_else(|| {
+            let mut styles = vec![AUTO_STYLE];
+            styles.extend(TranslationType::ALL.iter().map(|t| t.name()));
+            format!(
+                "Unknown translation style '{}', expected one of: {}",
+                style,
+                styles.join(", ")
+            )
+        }),
+    }
+}
 
-#[derive(Serialize, Deserialize)]
-struct GetTranslationErrorOutput {
-    error: String,
+/// A short, stable reason for why a translation failed, for clients to act on.
+fn fallback_reason(error: &ClientError) -> &'static str {
+    match error {
+        ClientError::TranslationTooManyRequestsError => "rate-limited",
+        ClientError::TranslationCircuitOpenError => "unavailable",
+        ClientError::TranslationTimeout => "timeout",
+        ClientError::TranslationDeserializationError(_) => "invalid-response",
+        ClientError::TranslationUnsupportedError => "unsupported",
+        ClientError::TranslationAPIError(_)
+        | ClientError::PokemonNotFoundError
+        | ClientError::PokemonDeserializationError(_)
+        | ClientError::PokemonAPIError(_)
+        | ClientError::PokemonAPITimeout => "upstream-error",
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn get(
-    pokemon_client: PokemonClient,
-    translation_client: TranslationClient,
+    pokemon_source: Arc<dyn PokemonSource>,
+    translator: Arc<dyn Translator>,
+    rules: Arc<TranslationRules>,
+    metrics: Arc<Metrics>,
     pokemon_name: String,
-) -> Result<impl warp::Reply, Infallible> {
-    let response = pokemon_client.get_pokemon(&pokemon_name).await;
+    query: TranslatedQuery,
+    path: FullPath,
+    context: RequestContext,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let style = parse_style(query.style.as_deref()).map_err(|detail| {
+        warp::reject::custom(ProblemRejection(Problem::new(
+            "invalid-translation-style",
+            "Invalid translation style",
+            StatusCode::BAD_REQUEST,
+            detail,
+            Some(path.as_str().into()),
+        )))
+    })?;
+
+    Span::current().record("pokemon", pokemon_name.as_str());
+    let response = pokemon_source.get_species(&pokemon_name, &context).await;
     match response {
         Ok(pokemon) => {
-            let mut translation_type = TranslationType::Shakespeare;
-            if pokemon.habitat.name == "cave" || pokemon.is_legendary {
-                translation_type = TranslationType::Yoda;
-            }
-
-            let description = match pokemon.get_description() {
-                Some(desc) => {
-                    let translation_response = translation_client
-                        .get_translation(&desc, translation_type)
+            let translation_type = style.or_else(|| rules.select(&pokemon));
+
+            let (description, translation) = match (pokemon.get_description(), translation_type) {
+                (Some(desc), Some(translation_type)) => {
+                    let translation_response = translator
+                        .translate(&desc, translation_type, &context)
                         .await;
                     match translation_response {
-                        Ok(translated_text) => Some(translated_text),
-                        Err(_) => Some(desc), // Swallowing error as task says to use standard description if we fail to translate
+                        Ok(translated) => (
+                            Some(translated.text),
+                            TranslationInfo {
+                                translation: Some(translation_type.name().into()),
+                                source: Some(translated.source.name().into()),
+                                fallback: false,
+                                fallback_reason: None,
+                            },
+                        ),
+                        // Use the standard description if we fail to translate, but let the caller know
+                        Err(translation_error) => {
+                            let reason = fallback_reason(&translation_error);
+                            warn!(
+                                error = %translation_error,
+                                reason,
+                                translation = translation_type.name(),
+                                "Failed to translate, using the standard description"
+                            );
+                            metrics.observe_fallback(reason);
+                            (
+                                Some(desc),
+                                TranslationInfo {
+                                    translation: None,
+                                    source: None,
+                                    fallback: true,
+                                    fallback_reason: Some(reason.into()),
+                                },
+                            )
+                        }
                     }
                 }
-                None => None,
+                // Nothing to translate, or no rule picked a translation
+                (description, None) | (description @ None, _) => (
+                    description,
+                    TranslationInfo {
+                        translation: None,
+                        source: None,
+                        fallback: false,
+                        fallback_reason: None,
+                    },
+                ),
             };
 
             let response = PokemonResponse {
@@ -44,6 +134,7 @@ pub async fn get(
                 description,
                 is_legendary: pokemon.is_legendary,
                 habitat: pokemon.habitat.name,
+                translation: Some(translation),
             };
 
             Ok(warp::reply::with_status(
@@ -51,20 +142,10 @@ pub async fn get(
                 warp::http::StatusCode::OK,
             ))
         }
-        Err(pokemon_error) => match pokemon_error {
-            client_error::ClientError::PokemonNotFoundError => Ok(warp::reply::with_status(
-                warp::reply::json(&GetTranslationErrorOutput {
-                    error: "Failed to find pokemon".into(),
-                }),
-                warp::http::StatusCode::NOT_FOUND,
-            )),
-            _ => Ok(warp::reply::with_status(
-                warp::reply::json(&GetTranslationErrorOutput {
-                    error: "Failed to get pokemon".into(),
-                }),
-                warp::http::StatusCode::INTERNAL_SERVER_ERROR,
-            )),
-        },
+        Err(pokemon_error) => {
+            warn!(error = %pokemon_error, "Failed to get pokemon");
+            Err(problem::reject(pokemon_error, path.as_str().into()))
+        }
     }
 }
 
@@ -72,19 +153,24 @@ pub async fn get(
 mod tests {
     use super::*;
 
-    use crate::client::{
-        pokemon_client::{FlavorTextEntry, Habitat, Language, Pokemon},
-        translation_client::{TranslationResponse, TranslationSuccess, TranslationTextContents},
+    use async_trait::async_trait;
+    use funtranslations_client::{
+        LocalTranslation, Timeouts, Translation, TranslationClient, TranslationResponse,
+        TranslationSource, TranslationSuccess, TranslationTextContents,
     };
+    use pokeapi_client::{FlavorTextEntry, Habitat, Language, Pokemon};
+    use serde::{Deserialize, Serialize};
     use serde_json::json;
+    use std::time::Duration;
 
-    use wiremock::matchers::{body_json, method, path};
+    use wiremock::matchers::{body_json, header, method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
     #[derive(Serialize, Deserialize, Debug, PartialEq)]
     struct TextInput {
         text: String,
     }
+
     #[tokio::test]
     async fn it_successfully_gets_the_translated_pokemon_text_for_non_cave_non_legendary_pokemon() {
         // arrange
@@ -116,6 +202,9 @@ mod tests {
 				},
             }],
             is_legendary: false,
+            is_mythical: false,
+            generation: None,
+            color: None,
             habitat: Habitat {
                 name: "urban".into(),
                 url: "https://pokeapi.co/api/v2/pokemon-habitat/8/".into(),
@@ -139,10 +228,7 @@ mod tests {
             .await;
 
         // act
-        let filter = crate::routes::routes(
-            PokemonClient::new(mock_server.uri()),
-            TranslationClient::new(mock_server.uri(), None),
-        );
+        let filter = crate::routes::test_routes(&mock_server.uri(), &mock_server.uri()).build();
         let res = warp::test::request()
             .method("GET")
             .path("/pokemon/translated/charizard")
@@ -151,7 +237,7 @@ mod tests {
 
         // assert
         assert_eq!(res.status(), 200);
-        assert_eq!(res.body(), "{\"name\":\"charizard\",\"description\":\"Spits fire yond is hot enow to melt boulders. Known to cause forest fires unintentionally.\",\"isLegendary\":false,\"habitat\":\"urban\"}");
+        assert_eq!(res.body(), "{\"name\":\"charizard\",\"description\":\"Spits fire yond is hot enow to melt boulders. Known to cause forest fires unintentionally.\",\"isLegendary\":false,\"habitat\":\"urban\",\"translation\":\"shakespeare\",\"translationSource\":\"funtranslations\",\"translationFallback\":false}");
     }
 
     #[tokio::test]
@@ -185,6 +271,9 @@ mod tests {
 				},
             }],
             is_legendary: false,
+            is_mythical: false,
+            generation: None,
+            color: None,
             habitat: Habitat {
                 name: "cave".into(),
                 url: "https://pokeapi.co/api/v2/pokemon-habitat/8/".into(),
@@ -208,10 +297,7 @@ mod tests {
             .await;
 
         // act
-        let filter = crate::routes::routes(
-            PokemonClient::new(mock_server.uri()),
-            TranslationClient::new(mock_server.uri(), None),
-        );
+        let filter = crate::routes::test_routes(&mock_server.uri(), &mock_server.uri()).build();
         let res = warp::test::request()
             .method("GET")
             .path("/pokemon/translated/charizard")
@@ -220,7 +306,7 @@ mod tests {
 
         // assert
         assert_eq!(res.status(), 200);
-        assert_eq!(res.body(), "{\"name\":\"zubat\",\"description\":\"Forms colonies in perpetually dark places.And approach targets, uses ultrasonic waves to identify.\",\"isLegendary\":false,\"habitat\":\"cave\"}");
+        assert_eq!(res.body(), "{\"name\":\"zubat\",\"description\":\"Forms colonies in perpetually dark places.And approach targets, uses ultrasonic waves to identify.\",\"isLegendary\":false,\"habitat\":\"cave\",\"translation\":\"yoda\",\"translationSource\":\"funtranslations\",\"translationFallback\":false}");
     }
 
     #[tokio::test]
@@ -254,6 +340,9 @@ mod tests {
 				},
             }],
             is_legendary: true,
+            is_mythical: false,
+            generation: None,
+            color: None,
             habitat: Habitat {
                 name: "urban".into(),
                 url: "https://pokeapi.co/api/v2/pokemon-habitat/8/".into(),
@@ -277,10 +366,7 @@ mod tests {
             .await;
 
         // act
-        let filter = crate::routes::routes(
-            PokemonClient::new(mock_server.uri()),
-            TranslationClient::new(mock_server.uri(), None),
-        );
+        let filter = crate::routes::test_routes(&mock_server.uri(), &mock_server.uri()).build();
         let res = warp::test::request()
             .method("GET")
             .path("/pokemon/translated/charizard")
@@ -289,7 +375,7 @@ mod tests {
 
         // assert
         assert_eq!(res.status(), 200);
-        assert_eq!(res.body(), "{\"name\":\"zubat\",\"description\":\"Forms colonies in perpetually dark places.And approach targets, uses ultrasonic waves to identify.\",\"isLegendary\":true,\"habitat\":\"urban\"}");
+        assert_eq!(res.body(), "{\"name\":\"zubat\",\"description\":\"Forms colonies in perpetually dark places.And approach targets, uses ultrasonic waves to identify.\",\"isLegendary\":true,\"habitat\":\"urban\",\"translation\":\"yoda\",\"translationSource\":\"funtranslations\",\"translationFallback\":false}");
     }
 
     #[tokio::test]
@@ -304,10 +390,7 @@ mod tests {
             .await;
 
         // act
-        let filter = crate::routes::routes(
-            PokemonClient::new(mock_server.uri()),
-            TranslationClient::new(mock_server.uri(), None),
-        );
+        let filter = crate::routes::test_routes(&mock_server.uri(), &mock_server.uri()).build();
         let res = warp::test::request()
             .method("GET")
             .path("/pokemon/translated/charizard")
@@ -316,7 +399,8 @@ mod tests {
 
         // assert
         assert_eq!(res.status(), 404);
-        assert_eq!(res.body(), "{\"error\":\"Failed to find pokemon\"}");
+        assert_eq!(res.headers()["content-type"], "application/problem+json");
+        assert_eq!(res.body(), "{\"type\":\"/problems/pokemon-not-found\",\"title\":\"Pokemon not found\",\"status\":404,\"detail\":\"Failed to find pokemon\",\"instance\":\"/pokemon/translated/charizard\"}");
     }
 
     #[tokio::test]
@@ -339,6 +423,9 @@ mod tests {
     		   },
     	   }],
             is_legendary: false,
+            is_mythical: false,
+            generation: None,
+            color: None,
             habitat: Habitat {
                 name: "urban".into(),
                 url: "https://pokeapi.co/api/v2/pokemon-habitat/8/".into(),
@@ -361,10 +448,134 @@ mod tests {
             .await;
 
         // act
-        let filter = crate::routes::routes(
-            PokemonClient::new(mock_server.uri()),
-            TranslationClient::new(mock_server.uri(), None),
-        );
+        let filter = crate::routes::test_routes(&mock_server.uri(), &mock_server.uri()).build();
+        let res = warp::test::request()
+            .method("GET")
+            .path("/pokemon/translated/charizard")
+            .reply(&filter)
+            .await;
+
+        // assert
+        assert_eq!(res.status(), 200);
+        assert_eq!(res.body(), "{\"name\":\"charizard\",\"description\":\"Spits fire that is hot enough to melt boulders. Known to cause forest fires unintentionally.\",\"isLegendary\":false,\"habitat\":\"urban\",\"translation\":null,\"translationFallback\":true,\"fallbackReason\":\"rate-limited\"}");
+    }
+
+    #[tokio::test]
+    async fn it_skips_translating_while_the_circuit_breaker_is_open() {
+        // arrange
+        let mock_server = MockServer::start().await;
+
+        let generated_pokemon = Pokemon {
+            id: 6,
+            name: "charizard".into(),
+            flavor_text_entries: vec![FlavorTextEntry {
+                flavor_text: "Spits fire that is hot enough to melt boulders.\nKnown to cause forest fires unintentionally.".into(),
+                language: Language {
+                    name: "en".into(),
+                    url: "https://pokeapi.co/api/v2/language/9/".into(),
+                },
+            }],
+            is_legendary: false,
+            is_mythical: false,
+            generation: None,
+            color: None,
+            habitat: Habitat {
+                name: "urban".into(),
+                url: "https://pokeapi.co/api/v2/pokemon-habitat/8/".into(),
+            },
+        };
+
+        let mock_pokemon_response =
+            ResponseTemplate::new(200).set_body_json(json!(generated_pokemon));
+
+        Mock::given(method("POST"))
+            .and(path("/translate/shakespeare.json"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/charizard"))
+            .respond_with(mock_pokemon_response)
+            .mount(&mock_server)
+            .await;
+
+        let filter = crate::routes::test_routes(&mock_server.uri(), &mock_server.uri())
+            .with_translator(
+                TranslationClient::new(mock_server.uri(), None)
+                    .with_circuit_breaker(1, Duration::from_secs(60)),
+            )
+            .build();
+
+        // act & assert
+        for expected_reason in &["upstream-error", "unavailable", "unavailable"] {
+            let res = warp::test::request()
+                .method("GET")
+                .path("/pokemon/translated/charizard")
+                .reply(&filter)
+                .await;
+
+            assert_eq!(res.status(), 200);
+            assert_eq!(
+                res.body(),
+                &format!("{{\"name\":\"charizard\",\"description\":\"Spits fire that is hot enough to melt boulders. Known to cause forest fires unintentionally.\",\"isLegendary\":false,\"habitat\":\"urban\",\"translation\":null,\"translationFallback\":true,\"fallbackReason\":\"{}\"}}", expected_reason)
+            );
+        }
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn it_returns_default_description_on_translation_timeout() {
+        // arrange
+        let mock_server = MockServer::start().await;
+
+        let generated_pokemon = Pokemon {
+            id: 6,
+            name: "charizard".into(),
+            flavor_text_entries: vec![FlavorTextEntry {
+                flavor_text: "Spits fire that is hot enough to melt boulders.\nKnown to cause forest fires unintentionally.".into(),
+                language: Language {
+                    name: "en".into(),
+                    url: "https://pokeapi.co/api/v2/language/9/".into(),
+                },
+            }],
+            is_legendary: false,
+            is_mythical: false,
+            generation: None,
+            color: None,
+            habitat: Habitat {
+                name: "urban".into(),
+                url: "https://pokeapi.co/api/v2/pokemon-habitat/8/".into(),
+            },
+        };
+
+        let mock_pokemon_response =
+            ResponseTemplate::new(200).set_body_json(json!(generated_pokemon));
+
+        Mock::given(method("POST"))
+            .and(path("/translate/shakespeare.json"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(5)))
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/charizard"))
+            .respond_with(mock_pokemon_response)
+            .mount(&mock_server)
+            .await;
+
+        // act
+        let filter = crate::routes::test_routes(&mock_server.uri(), &mock_server.uri())
+            .with_translator(
+                TranslationClient::new(mock_server.uri(), None)
+                    .with_timeouts(Timeouts {
+                        connect: Duration::from_secs(1),
+                        total: Duration::from_millis(100),
+                    })
+                    .unwrap(),
+            )
+            .build();
         let res = warp::test::request()
             .method("GET")
             .path("/pokemon/translated/charizard")
@@ -373,6 +584,333 @@ mod tests {
 
         // assert
         assert_eq!(res.status(), 200);
-        assert_eq!(res.body(), "{\"name\":\"charizard\",\"description\":\"Spits fire that is hot enough to melt boulders. Known to cause forest fires unintentionally.\",\"isLegendary\":false,\"habitat\":\"urban\"}");
+        assert_eq!(res.body(), "{\"name\":\"charizard\",\"description\":\"Spits fire that is hot enough to melt boulders. Known to cause forest fires unintentionally.\",\"isLegendary\":false,\"habitat\":\"urban\",\"translation\":null,\"translationFallback\":true,\"fallbackReason\":\"timeout\"}");
+    }
+
+    fn zubat() -> Pokemon {
+        Pokemon {
+            id: 41,
+            name: "zubat".into(),
+            flavor_text_entries: vec![FlavorTextEntry {
+                flavor_text: "Forms colonies in perpetually dark places.".into(),
+                language: Language {
+                    name: "en".into(),
+                    url: "https://pokeapi.co/api/v2/language/9/".into(),
+                },
+            }],
+            is_legendary: false,
+            is_mythical: false,
+            generation: None,
+            color: None,
+            habitat: Habitat {
+                name: "cave".into(),
+                url: "https://pokeapi.co/api/v2/pokemon-habitat/1/".into(),
+            },
+        }
+    }
+
+    fn translated(text: &str, translation: &str) -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(json!(TranslationResponse {
+            success: TranslationSuccess { total: 1 },
+            contents: TranslationTextContents {
+                translated: text.into(),
+                text: "Forms colonies in perpetually dark places.".into(),
+                translation: translation.into(),
+            },
+        }))
+    }
+
+    #[tokio::test]
+    async fn it_uses_the_requested_translation_style() {
+        // arrange
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/translate/shakespeare.json"))
+            .respond_with(translated(
+                "Forms colonies in perpetually dark places, forsooth.",
+                "shakespeare",
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/zubat"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(zubat())))
+            .mount(&mock_server)
+            .await;
+
+        // act
+        let filter = crate::routes::test_routes(&mock_server.uri(), &mock_server.uri()).build();
+        let res = warp::test::request()
+            .method("GET")
+            .path("/pokemon/translated/zubat?style=shakespeare")
+            .reply(&filter)
+            .await;
+
+        // assert
+        assert_eq!(res.status(), 200);
+        assert_eq!(res.body(), "{\"name\":\"zubat\",\"description\":\"Forms colonies in perpetually dark places, forsooth.\",\"isLegendary\":false,\"habitat\":\"cave\",\"translation\":\"shakespeare\",\"translationSource\":\"funtranslations\",\"translationFallback\":false}");
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn it_picks_the_translation_style_when_auto() {
+        // arrange
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/translate/yoda.json"))
+            .respond_with(translated(
+                "In perpetually dark places, colonies it forms.",
+                "yoda",
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/zubat"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(zubat())))
+            .mount(&mock_server)
+            .await;
+
+        // act
+        let filter = crate::routes::test_routes(&mock_server.uri(), &mock_server.uri()).build();
+        let res = warp::test::request()
+            .method("GET")
+            .path("/pokemon/translated/zubat?style=auto")
+            .reply(&filter)
+            .await;
+
+        // assert
+        assert_eq!(res.status(), 200);
+        assert_eq!(res.body(), "{\"name\":\"zubat\",\"description\":\"In perpetually dark places, colonies it forms.\",\"isLegendary\":false,\"habitat\":\"cave\",\"translation\":\"yoda\",\"translationSource\":\"funtranslations\",\"translationFallback\":false}");
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn it_returns_400_on_an_invalid_translation_style() {
+        // arrange
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(zubat())))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        // act
+        let filter = crate::routes::test_routes(&mock_server.uri(), &mock_server.uri()).build();
+        let res = warp::test::request()
+            .method("GET")
+            .path("/pokemon/translated/zubat?style=parseltongue")
+            .reply(&filter)
+            .await;
+
+        // assert
+        assert_eq!(res.status(), 400);
+        assert_eq!(res.headers()["content-type"], "application/problem+json");
+        let body: serde_json::Value = serde_json::from_slice(res.body()).unwrap();
+        assert_eq!(body["type"], "/problems/invalid-translation-style");
+        assert_eq!(body["status"], 400);
+        assert_eq!(body["instance"], "/pokemon/translated/zubat");
+        assert!(body["detail"].as_str().unwrap().starts_with(
+            "Unknown translation style 'parseltongue', expected one of: auto, yoda, shakespeare, pirate,"
+        ));
+        mock_server.verify().await;
+    }
+
+    struct FakePokemonSource(Pokemon);
+
+    #[async_trait]
+    impl PokemonSource for FakePokemonSource {
+        async fn get_species(
+            &self,
+            _name: &str,
+            _context: &RequestContext,
+        ) -> Result<Pokemon, ClientError> {
+            Ok(self.0.clone())
+        }
+
+        async fn check_health(&self) -> Result<(), ClientError> {
+            Ok(())
+        }
+    }
+
+    struct FakeTranslator(Result<String, ClientError>);
+
+    #[async_trait]
+    impl Translator for FakeTranslator {
+        async fn translate(
+            &self,
+            _text: &str,
+            _translation_type: TranslationType,
+            _context: &RequestContext,
+        ) -> Result<Translation, ClientError> {
+            self.0.clone().map(|text| Translation {
+                text,
+                source: TranslationSource::Funtranslations,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn it_translates_with_any_translator() {
+        // arrange
+        let filter = crate::routes::test_routes("http://127.0.0.1:9", "http://127.0.0.1:9")
+            .with_pokemon_source(FakePokemonSource(zubat()))
+            .with_translator(FakeTranslator(Ok("Hmm, colonies it forms.".into())))
+            .build();
+
+        // act
+        let res = warp::test::request()
+            .method("GET")
+            .path("/pokemon/translated/zubat")
+            .reply(&filter)
+            .await;
+
+        // assert
+        assert_eq!(res.status(), 200);
+        assert_eq!(res.body(), "{\"name\":\"zubat\",\"description\":\"Hmm, colonies it forms.\",\"isLegendary\":false,\"habitat\":\"cave\",\"translation\":\"yoda\",\"translationSource\":\"funtranslations\",\"translationFallback\":false}");
+    }
+
+    #[tokio::test]
+    async fn it_reports_translating_offline() {
+        // arrange
+        let filter = crate::routes::test_routes("http://127.0.0.1:9", "http://127.0.0.1:9")
+            .with_pokemon_source(FakePokemonSource(zubat()))
+            .with_translator(
+                TranslationClient::new("http://127.0.0.1:9".into(), None)
+                    .with_local_translation(LocalTranslation::Fallback),
+            )
+            .build();
+
+        // act
+        let res = warp::test::request()
+            .method("GET")
+            .path("/pokemon/translated/zubat?style=shakespeare")
+            .reply(&filter)
+            .await;
+
+        // assert
+        assert_eq!(res.status(), 200);
+        let body: serde_json::Value = serde_json::from_slice(res.body()).unwrap();
+        assert_eq!(body["translation"], "shakespeare");
+        assert_eq!(body["translationSource"], "offline");
+        assert_eq!(body["translationFallback"], false);
+    }
+
+    #[tokio::test]
+    async fn it_reports_why_any_translator_failed() {
+        // arrange
+        let metrics = Arc::new(Metrics::new());
+        let filter = crate::routes::test_routes("http://127.0.0.1:9", "http://127.0.0.1:9")
+            .with_pokemon_source(FakePokemonSource(zubat()))
+            .with_translator(FakeTranslator(Err(
+                ClientError::TranslationCircuitOpenError,
+            )))
+            .with_metrics(metrics.clone())
+            .build();
+
+        // act
+        let res = warp::test::request()
+            .method("GET")
+            .path("/pokemon/translated/zubat")
+            .reply(&filter)
+            .await;
+
+        // assert
+        assert_eq!(res.status(), 200);
+        let body: serde_json::Value = serde_json::from_slice(res.body()).unwrap();
+        assert_eq!(body["translationFallback"], true);
+        assert_eq!(body["fallbackReason"], "unavailable");
+        assert!(metrics
+            .render()
+            .contains("translation_fallbacks_total{reason=\"unavailable\"} 1"));
+    }
+
+    #[test]
+    fn it_parses_translation_styles() {
+        // arrange
+        let cases = vec![
+            (None, Ok(None)),
+            (Some("auto"), Ok(None)),
+            (Some("yoda"), Ok(Some(TranslationType::YODA))),
+            (Some("shakespeare"), Ok(Some(TranslationType::SHAKESPEARE))),
+            (
+                Some("pig-latin"),
+                Ok(TranslationType::from_name("pig-latin")),
+            ),
+        ];
+
+        for (style, expected) in cases {
+            // act
+            let parsed = parse_style(style);
+
+            // assert
+            assert_eq!(parsed, expected, "{:?}", style);
+        }
+    }
+
+    #[test]
+    fn it_rejects_translation_styles_in_the_wrong_case() {
+        // act
+        let parsed = parse_style(Some("Yoda"));
+
+        // assert
+        assert!(parsed.is_err());
+    }
+
+    #[tokio::test]
+    async fn it_forwards_the_request_id_and_trace_to_both_upstreams() {
+        // arrange
+        let mock_server = MockServer::start().await;
+        let trace_id = "4bf92f3577b34da6a3ce929d0e0e4736";
+        let traceparent = format!("00-{}-00f067aa0ba902b7-01", trace_id);
+
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/zubat"))
+            .and(header("x-request-id", "abc-123"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(zubat())))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/translate/yoda.json"))
+            .and(header("x-request-id", "abc-123"))
+            .respond_with(translated("Hmm, colonies it forms.", "yoda"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let filter = crate::routes::test_routes(&mock_server.uri(), &mock_server.uri()).build();
+
+        // act
+        let res = warp::test::request()
+            .method("GET")
+            .path("/pokemon/translated/zubat")
+            .header("x-request-id", "abc-123")
+            .header("traceparent", &traceparent)
+            .reply(&filter)
+            .await;
+
+        // assert
+        assert_eq!(res.status(), 200);
+        assert_eq!(res.headers()["x-request-id"], "abc-123");
+        let echoed_traceparent = res.headers()["traceparent"].to_str().unwrap();
+        assert!(echoed_traceparent.starts_with(&format!("00-{}-", trace_id)));
+        mock_server.verify().await;
+
+        // Both upstreams get the same traceparent as the response, as a child of the caller's
+        for request in mock_server.received_requests().await.unwrap() {
+            let traceparent = request
+                .headers
+                .iter()
+                .find(|(name, _)| name.as_str() == "traceparent")
+                .map(|(_, value)| value.as_str());
+            assert_eq!(traceparent, Some(echoed_traceparent));
+        }
     }
 }
@@ -0,0 +1,58 @@
+use funtranslations_client::TranslationType;
+use serde::Serialize;
+use std::convert::Infallible;
+
+#[derive(Serialize)]
+struct TranslationStyle {
+    name: &'static str,
+    #[serde(rename = "displayName")]
+    display_name: &'static str,
+}
+
+#[derive(Serialize)]
+struct TranslationsResponse {
+    translations: Vec<TranslationStyle>,
+}
+
+pub async fn get() -> Result<impl warp::Reply, Infallible> {
+    let translations = TranslationType::ALL
+        .iter()
+        .map(|translation_type| TranslationStyle {
+            name: translation_type.name(),
+            display_name: translation_type.display_name(),
+        })
+        .collect();
+    Ok(warp::reply::json(&TranslationsResponse { translations }))
+}
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    async fn it_lists_the_available_translations() {
+        // arrange
+        let filter = crate::routes::test_routes("http://127.0.0.1:9", "http://127.0.0.1:9").build();
+
+        // act
+        let res = warp::test::request()
+            .method("GET")
+            .path("/translations")
+            .reply(&filter)
+            .await;
+
+        // assert
+        assert_eq!(res.status(), 200);
+        let body: serde_json::Value = serde_json::from_slice(res.body()).unwrap();
+        let translations = body["translations"].as_array().unwrap();
+        assert_eq!(
+            translations[0],
+            serde_json::json!({"name": "yoda", "displayName": "Yoda"})
+        );
+        assert_eq!(
+            translations[1],
+            serde_json::json!({"name": "shakespeare", "displayName": "Shakespeare"})
+        );
+        assert!(
+            translations.contains(&serde_json::json!({"name": "pirate", "displayName": "Pirate"}))
+        );
+    }
+}
@@ -0,0 +1,365 @@
+use funtranslations_client::TranslationType;
+use pokeapi_client::Pokemon;
+use serde::Deserialize;
+use std::path::Path;
+use thiserror::Error;
+
+const HABITATS: [&str; 9] = [
+    "cave",
+    "forest",
+    "grassland",
+    "mountain",
+    "rare",
+    "rough-terrain",
+    "sea",
+    "urban",
+    "waters-edge",
+];
+
+const COLORS: [&str; 10] = [
+    "black", "blue", "brown", "gray", "green", "pink", "purple", "red", "white", "yellow",
+];
+
+#[derive(Error, Debug)]
+pub enum RulesError {
+    #[error("Failed to read translation rules file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to parse translation rules: {0}")]
+    Parse(#[from] toml::de::Error),
+    #[error("Invalid translation rules:\n{}", .0.join("\n"))]
+    Invalid(Vec<String>),
+}
+
+/// A rule as written in the rules file. Every condition that is set must match the pokemon.
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct RuleDefinition {
+    habitat: Option<String>,
+    legendary: Option<bool>,
+    mythical: Option<bool>,
+    generation: Option<String>,
+    color: Option<String>,
+    translation: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct RulesFile {
+    #[serde(default)]
+    rules: Vec<RuleDefinition>,
+}
+
+#[derive(Debug, PartialEq)]
+struct Rule {
+    habitat: Option<String>,
+    legendary: Option<bool>,
+    mythical: Option<bool>,
+    generation: Option<String>,
+    color: Option<String>,
+    translation: TranslationType,
+}
+
+impl Rule {
+    fn matches(&self, pokemon: &Pokemon) -> bool {
+        let generation = pokemon.generation.as_ref().map(|g| g.name.as_str());
+        let color = pokemon.color.as_ref().map(|c| c.name.as_str());
+
+        self.habitat
+            .as_ref()
+            .is_none_or(|habitat| *habitat == pokemon.habitat.name)
+            && self
+                .legendary
+                .is_none_or(|legendary| legendary == pokemon.is_legendary)
+            && self
+                .mythical
+                .is_none_or(|mythical| mythical == pokemon.is_mythical)
+            && self
+                .generation
+                .as_deref()
+                .is_none_or(|expected| Some(expected) == generation)
+            && self
+                .color
+                .as_deref()
+                .is_none_or(|expected| Some(expected) == color)
+    }
+}
+
+/// An ordered list of rules choosing which translation a pokemon gets. The first matching rule wins.
+#[derive(Debug, PartialEq)]
+pub struct TranslationRules {
+    rules: Vec<Rule>,
+}
+
+impl TranslationRules {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, RulesError> {
+        Self::from_toml(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn from_toml(rules: &str) -> Result<Self, RulesError> {
+        let file: RulesFile = toml::from_str(rules)?;
+
+        let mut problems = vec![];
+        let mut rules = vec![];
+        for (index, definition) in file.rules.into_iter().enumerate() {
+            let rule_number = index + 1;
+            if let Some(habitat) = &definition.habitat {
+                if !HABITATS.contains(&habitat.as_str()) {
+                    problems.push(format!(
+                        "Rule {}: unknown habitat '{}', expected one of: {}",
+                        rule_number,
+                        habitat,
+                        HABITATS.join(", ")
+                    ));
+                }
+            }
+            if let Some(color) = &definition.color {
+                if !COLORS.contains(&color.as_str()) {
+                    problems.push(format!(
+                        "Rule {}: unknown color '{}', expected one of: {}",
+                        rule_number,
+                        color,
+                        COLORS.join(", ")
+                    ));
+                }
+            }
+            if let Some(generation) = &definition.generation {
+                if !is_generation_name(generation) {
+                    problems.push(format!(
+                        "Rule {}: invalid generation '{}', expected a name like 'generation-iv'",
+                        rule_number, generation
+                    ));
+                }
+            }
+            match TranslationType::from_name(&definition.translation) {
+                Some(translation) => rules.push(Rule {
+                    habitat: definition.habitat,
+                    legendary: definition.legendary,
+                    mythical: definition.mythical,
+                    generation: definition.generation,
+                    color: definition.color,
+                    translation,
+                }),
+                None => problems.push(format!(
+                    "Rule {}: unknown translation '{}', expected one of: {}",
+                    rule_number,
+                    definition.translation,
+                    TranslationType::ALL
+                        .iter()
+                        .map(|t| t.name())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )),
+            }
+        }
+
+        if !problems.is_empty() {
+            return Err(RulesError::Invalid(problems));
+        }
+        Ok(Self { rules })
+    }
+
+    /// Picks the translation for the first rule the pokemon matches, if any.
+    pub fn select(&self, pokemon: &Pokemon) -> Option<TranslationType> {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(pokemon))
+            .map(|rule| rule.translation)
+    }
+}
+
+impl Default for TranslationRules {
+    /// Cave dwelling and legendary pokemon get a yoda translation, everything else gets shakespeare.
+    fn default() -> Self {
+        let rule = |habitat: Option<&str>, legendary, translation| Rule {
+            habitat: habitat.map(String::from),
+            legendary,
+            mythical: None,
+            generation: None,
+            color: None,
+            translation,
+        };
+        Self {
+            rules: vec![
+                rule(Some("cave"), None, TranslationType::YODA),
+                rule(None, Some(true), TranslationType::YODA),
+                rule(None, None, TranslationType::SHAKESPEARE),
+            ],
+        }
+    }
+}
+
+fn is_generation_name(generation: &str) -> bool {
+    match generation.strip_prefix("generation-") {
+        Some(numeral) => {
+            !numeral.is_empty() && numeral.chars().all(|c| matches!(c, 'i' | 'v' | 'x'))
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pokeapi_client::{Color, Generation, Habitat};
+
+    fn pokemon(habitat: &str, is_legendary: bool, is_mythical: bool) -> Pokemon {
+        Pokemon {
+            id: 151,
+            name: "mew".into(),
+            is_legendary,
+            is_mythical,
+            habitat: Habitat {
+                name: habitat.into(),
+                url: "https://pokeapi.co/api/v2/pokemon-habitat/5/".into(),
+            },
+            generation: Some(Generation {
+                name: "generation-i".into(),
+                url: "https://pokeapi.co/api/v2/generation/1/".into(),
+            }),
+            color: Some(Color {
+                name: "pink".into(),
+                url: "https://pokeapi.co/api/v2/pokemon-color/6/".into(),
+            }),
+            flavor_text_entries: vec![],
+        }
+    }
+
+    #[test]
+    fn it_defaults_to_yoda_for_cave_and_legendary_pokemon() {
+        // arrange
+        let rules = TranslationRules::default();
+
+        // act & assert
+        assert_eq!(
+            rules.select(&pokemon("cave", false, false)),
+            Some(TranslationType::YODA)
+        );
+        assert_eq!(
+            rules.select(&pokemon("rare", true, false)),
+            Some(TranslationType::YODA)
+        );
+        assert_eq!(
+            rules.select(&pokemon("urban", false, false)),
+            Some(TranslationType::SHAKESPEARE)
+        );
+    }
+
+    #[test]
+    fn it_uses_the_first_matching_rule() {
+        // arrange
+        let rules = TranslationRules::from_toml(
+            r#"
+            [[rules]]
+            mythical = true
+            translation = "yoda"
+
+            [[rules]]
+            generation = "generation-i"
+            color = "pink"
+            translation = "shakespeare"
+            "#,
+        )
+        .unwrap();
+
+        // act & assert
+        assert_eq!(
+            rules.select(&pokemon("rare", false, true)),
+            Some(TranslationType::YODA)
+        );
+        assert_eq!(
+            rules.select(&pokemon("rare", false, false)),
+            Some(TranslationType::SHAKESPEARE)
+        );
+    }
+
+    #[test]
+    fn it_selects_nothing_when_no_rule_matches() {
+        // arrange
+        let rules = TranslationRules::from_toml(
+            r#"
+            [[rules]]
+            habitat = "sea"
+            translation = "yoda"
+            "#,
+        )
+        .unwrap();
+
+        // act & assert
+        assert_eq!(rules.select(&pokemon("cave", false, false)), None);
+    }
+
+    #[test]
+    fn it_parses_the_default_rules() {
+        // arrange
+        let rules = TranslationRules::from_toml(
+            r#"
+            [[rules]]
+            habitat = "cave"
+            translation = "yoda"
+
+            [[rules]]
+            legendary = true
+            translation = "yoda"
+
+            [[rules]]
+            translation = "shakespeare"
+            "#,
+        )
+        .unwrap();
+
+        // act & assert
+        assert_eq!(rules, TranslationRules::default());
+    }
+
+    #[test]
+    fn it_reports_every_invalid_rule() {
+        // act
+        let error = TranslationRules::from_toml(
+            r#"
+            [[rules]]
+            habitat = "space"
+            translation = "yoda"
+
+            [[rules]]
+            color = "teal"
+            generation = "gen-1"
+            translation = "parseltongue"
+            "#,
+        )
+        .unwrap_err();
+
+        // assert
+        let translations = TranslationType::ALL
+            .iter()
+            .map(|t| t.name())
+            .collect::<Vec<_>>()
+            .join(", ");
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "Invalid translation rules:\n\
+                Rule 1: unknown habitat 'space', expected one of: cave, forest, grassland, mountain, rare, rough-terrain, sea, urban, waters-edge\n\
+                Rule 2: unknown color 'teal', expected one of: black, blue, brown, gray, green, pink, purple, red, white, yellow\n\
+                Rule 2: invalid generation 'gen-1', expected a name like 'generation-iv'\n\
+                Rule 2: unknown translation 'parseltongue', expected one of: {}",
+                translations
+            )
+        );
+    }
+
+    #[test]
+    fn it_rejects_unknown_conditions() {
+        // act
+        let error = TranslationRules::from_toml(
+            r#"
+            [[rules]]
+            shiny = true
+            translation = "yoda"
+            "#,
+        )
+        .unwrap_err();
+
+        // assert
+        assert!(matches!(error, RulesError::Parse(_)));
+    }
+}
@@ -0,0 +1,56 @@
+use serde::Deserialize;
+use std::fmt;
+use std::path::Path;
+
+/// A value that shouldn't end up in logs, so it's redacted from `Debug` output.
+#[derive(Deserialize, Clone, PartialEq)]
+#[serde(transparent)]
+pub struct Secret(String);
+
+impl Secret {
+    /// Reads a secret from a file, such as a docker or kubernetes secret, ignoring surrounding whitespace.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        Ok(Self(std::fs::read_to_string(path)?.trim().to_string()))
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret([redacted])")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn it_redacts_debug_output() {
+        // arrange
+        let secret = Secret("hunter2".into());
+
+        // act
+        let debug = format!("{:?}", Some(secret));
+
+        // assert
+        assert_eq!(debug, "Some(Secret([redacted]))");
+    }
+
+    #[test]
+    fn it_reads_a_secret_from_a_file() {
+        // arrange
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "hunter2").unwrap();
+
+        // act
+        let secret = Secret::from_file(file.path()).unwrap();
+
+        // assert
+        assert_eq!(secret.expose(), "hunter2");
+    }
+}
@@ -0,0 +1,232 @@
+use crate::readiness::Readiness;
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tracing::{error, info, warn};
+use warp::{Filter, Reply};
+
+/// Resolves when the process is asked to stop, by SIGTERM (such as `docker stop`) or SIGINT.
+pub async fn signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => tokio::select! {
+                _ = terminate.recv() => info!("Received SIGTERM"),
+                _ = tokio::signal::ctrl_c() => info!("Received SIGINT"),
+            },
+            Err(error) => {
+                error!(%error, "Failed to listen for SIGTERM, only stopping on SIGINT");
+                let _ = tokio::signal::ctrl_c().await;
+                info!("Received SIGINT");
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+        info!("Received ctrl-c");
+    }
+}
+
+/// Resolves once `readiness` has been failing for `delay` after `signal`, so probes see the
+/// server isn't ready and send traffic elsewhere before it stops accepting connections.
+pub async fn fail_readiness(
+    signal: impl Future<Output = ()>,
+    readiness: Arc<Readiness>,
+    delay: Duration,
+) {
+    signal.await;
+    readiness.start_shutdown();
+    info!(
+        delay_ms = delay.as_millis() as u64,
+        "Failing readiness before stopping"
+    );
+    tokio::time::sleep(delay).await;
+}
+
+/// Serves `filter` on `addr` until `shutdown` resolves, then stops accepting connections and
+/// waits up to `drain_timeout` for in flight requests to finish.
+///
+/// Returns the bound address, and a future that runs the server and resolves to whether every
+/// in flight request finished in time, or an error if `addr` can't be bound.
+pub fn serve<F>(
+    filter: F,
+    addr: SocketAddr,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+    drain_timeout: Duration,
+) -> Result<(SocketAddr, impl Future<Output = bool>), warp::Error>
+where
+    F: Filter<Error = Infallible> + Clone + Send + Sync + 'static,
+    F::Extract: Reply,
+{
+    let (stop, stopped) = oneshot::channel::<()>();
+    let (addr, server) = warp::serve(filter).try_bind_with_graceful_shutdown(addr, async {
+        let _ = stopped.await;
+    })?;
+
+    let drained = async move {
+        let server = tokio::spawn(server);
+        shutdown.await;
+        info!(
+            drain_timeout_ms = drain_timeout.as_millis() as u64,
+            "Draining in flight requests"
+        );
+        let _ = stop.send(());
+        match tokio::time::timeout(drain_timeout, server).await {
+            Ok(_) => true,
+            Err(_) => {
+                warn!("In flight requests didn't finish before the drain timeout");
+                false
+            }
+        }
+    };
+    Ok((addr, drained))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    /// Responds after `delay`, so requests are still in flight when shutting down.
+    fn slow_filter(
+        delay: Duration,
+    ) -> impl Filter<Extract = (&'static str,), Error = Infallible> + Clone {
+        warp::any().and_then(move || async move {
+            tokio::time::sleep(delay).await;
+            Ok::<_, Infallible>("done")
+        })
+    }
+
+    async fn request(addr: SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!(
+            "GET {} HTTP/1.1\r\nhost: localhost\r\nconnection: close\r\n\r\n",
+            path
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        let _ = stream.read_to_string(&mut response).await;
+        response
+    }
+
+    #[tokio::test]
+    async fn it_finishes_in_flight_requests_before_stopping() {
+        // arrange
+        let (shutdown, shutdown_requested) = oneshot::channel::<()>();
+        let (addr, server) = serve(
+            slow_filter(Duration::from_millis(100)),
+            ([127, 0, 0, 1], 0).into(),
+            async {
+                let _ = shutdown_requested.await;
+            },
+            Duration::from_secs(5),
+        )
+        .unwrap();
+        let server = tokio::spawn(server);
+        let in_flight = tokio::spawn(request(addr, "/"));
+        tokio::time::sleep(Duration::from_millis(30)).await;
+
+        // act
+        shutdown.send(()).unwrap();
+        let drained = server.await.unwrap();
+
+        // assert
+        assert!(drained);
+        let response = in_flight.await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+        assert!(response.ends_with("done"), "{}", response);
+        assert!(TcpStream::connect(addr).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn it_gives_up_on_requests_after_the_drain_timeout() {
+        // arrange
+        let (shutdown, shutdown_requested) = oneshot::channel::<()>();
+        let (addr, server) = serve(
+            slow_filter(Duration::from_secs(60)),
+            ([127, 0, 0, 1], 0).into(),
+            async {
+                let _ = shutdown_requested.await;
+            },
+            Duration::from_millis(50),
+        )
+        .unwrap();
+        let server = tokio::spawn(server);
+        tokio::spawn(request(addr, "/"));
+        tokio::time::sleep(Duration::from_millis(30)).await;
+
+        // act
+        shutdown.send(()).unwrap();
+        let drained = server.await.unwrap();
+
+        // assert
+        assert!(!drained);
+    }
+
+    #[tokio::test]
+    async fn it_fails_readiness_while_still_accepting_connections() {
+        // arrange
+        let readiness = Arc::new(Readiness::default());
+        let filter = crate::routes::test_routes("http://127.0.0.1:9", "http://127.0.0.1:9")
+            .with_readiness(readiness.clone())
+            .build();
+        let (shutdown, shutdown_requested) = oneshot::channel::<()>();
+        let (addr, server) = serve(
+            filter,
+            ([127, 0, 0, 1], 0).into(),
+            fail_readiness(
+                async {
+                    let _ = shutdown_requested.await;
+                },
+                readiness,
+                Duration::from_millis(200),
+            ),
+            Duration::from_secs(5),
+        )
+        .unwrap();
+        let server = tokio::spawn(server);
+
+        // act
+        shutdown.send(()).unwrap();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        let response = request(addr, "/readyz").await;
+
+        // assert
+        assert!(
+            response.starts_with("HTTP/1.1 503 Service Unavailable"),
+            "{}",
+            response
+        );
+        assert!(
+            response.ends_with("{\"status\":\"shuttingDown\"}"),
+            "{}",
+            response
+        );
+        assert!(server.await.unwrap());
+        assert!(TcpStream::connect(addr).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn it_fails_to_serve_on_an_address_in_use() {
+        // arrange
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // act
+        let result = serve(
+            slow_filter(Duration::from_millis(0)),
+            addr,
+            async {},
+            Duration::from_secs(5),
+        );
+
+        // assert
+        assert!(result.is_err());
+    }
+}
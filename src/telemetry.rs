@@ -0,0 +1,115 @@
+use serde::Deserialize;
+use tracing::Subscriber;
+use tracing_subscriber::fmt::{format::FmtSpan, MakeWriter};
+use tracing_subscriber::EnvFilter;
+
+/// How logs are written to stdout.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Multi-line, human readable logs for local development
+    Pretty,
+    /// One JSON object per line, for log aggregation
+    Json,
+}
+
+/// Builds a subscriber writing logs in the given format. Spans are logged when they close, so
+/// every request and upstream call is logged with how long it took.
+/// The level is read from `RUST_LOG`, defaulting to info.
+fn subscriber<W>(format: LogFormat, writer: W) -> Box<dyn Subscriber + Send + Sync>
+where
+    W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+{
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_writer(writer);
+    match format {
+        LogFormat::Pretty => Box::new(builder.pretty().finish()),
+        LogFormat::Json => Box::new(builder.json().with_current_span(true).finish()),
+    }
+}
+
+/// Installs the global tracing subscriber, writing to stdout.
+pub fn init(format: LogFormat) {
+    if let Err(error) = tracing::subscriber::set_global_default(subscriber(format, std::io::stdout))
+    {
+        panic!("Failed to install the tracing subscriber: {}", error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn it_logs_requests_and_errors_as_json() {
+        // arrange
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/pokemon-species/missingno"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&mock_server)
+            .await;
+
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let _guard =
+            tracing::subscriber::set_default(subscriber(LogFormat::Json, move || writer.clone()));
+
+        let filter = crate::routes::test_routes(&mock_server.uri(), &mock_server.uri()).build();
+
+        // act
+        warp::test::request()
+            .method("GET")
+            .path("/pokemon/missingno")
+            .reply(&filter)
+            .await;
+
+        // assert
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        let retryable_status = lines
+            .iter()
+            .find(|line| line["fields"]["message"] == "Pokeapi responded with a retryable status")
+            .unwrap();
+        assert_eq!(retryable_status["level"], "WARN");
+        assert_eq!(retryable_status["span"]["name"], "pokeapi_request");
+        assert_eq!(retryable_status["span"]["status"], 500);
+
+        let failed = lines
+            .iter()
+            .find(|line| line["fields"]["message"] == "Failed to get pokemon")
+            .unwrap();
+        assert_eq!(
+            failed["fields"]["error"],
+            "Failed to get pokemon: status 500"
+        );
+        assert_eq!(failed["span"]["name"], "request");
+        assert_eq!(failed["span"]["route"], "pokemon");
+        assert_eq!(failed["span"]["pokemon"], "missingno");
+        assert!(failed["span"]["request_id"].is_string());
+    }
+}